@@ -0,0 +1,346 @@
+//! On-disk cache of remote guides, revalidated with HTTP conditional requests.
+
+use std::fs::{self, File};
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use reqwest::blocking::Client;
+use reqwest::header::{ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED};
+use reqwest::StatusCode;
+
+use crate::error::{Result, TvprogError};
+
+/// How long a cached guide is used without asking the server.
+pub const DEFAULT_MAX_AGE: Duration = Duration::from_secs(3600);
+
+/// A directory holding the last payload of every fetched URL, along with its
+/// `ETag` and `Last-Modified` validators.
+#[derive(Clone, Debug)]
+pub struct Cache {
+    pub dir: PathBuf,
+    pub max_age: Duration,
+    pub offline: bool,
+}
+
+#[derive(Debug, Default)]
+struct Validators {
+    etag: Option<String>,
+    last_modified: Option<String>,
+}
+
+impl Cache {
+    /// A cache in `dir` with the default settings.
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Cache {
+            dir: dir.into(),
+            max_age: DEFAULT_MAX_AGE,
+            offline: false,
+        }
+    }
+
+    /// `$XDG_CACHE_HOME/tvprog`, or its platform equivalent.
+    pub fn default_dir() -> Option<PathBuf> {
+        dirs::cache_dir().map(|dir| dir.join("tvprog"))
+    }
+
+    /// Returns the path of the cached guide at `url`, downloading it only when
+    /// the cached copy is older than [`Cache::max_age`] and the server reports
+    /// a change.
+    pub fn fetch(&self, url: &str) -> Result<PathBuf> {
+        let payload_path = self.payload_path(url);
+        let cached = payload_path.is_file();
+
+        if self.offline {
+            return match cached {
+                true => Ok(payload_path),
+                false => Err(TvprogError::NotCached(url.to_owned())),
+            };
+        }
+        if cached && self.is_fresh(&payload_path) {
+            return Ok(payload_path);
+        }
+
+        let validators = match cached {
+            true => self.read_validators(url)?,
+            false => Validators::default(),
+        };
+        let mut request = Client::new().get(url);
+        if let Some(etag) = &validators.etag {
+            request = request.header(IF_NONE_MATCH, etag);
+        }
+        if let Some(last_modified) = &validators.last_modified {
+            request = request.header(IF_MODIFIED_SINCE, last_modified);
+        }
+        let res = request.send()?;
+
+        if cached && res.status() == StatusCode::NOT_MODIFIED {
+            self.touch(&payload_path)?;
+            return Ok(payload_path);
+        }
+
+        let res = res.error_for_status()?;
+        let validators = Validators {
+            etag: header(&res, ETAG),
+            last_modified: header(&res, LAST_MODIFIED),
+        };
+        self.store(url, res, &validators)?;
+
+        Ok(payload_path)
+    }
+
+    fn is_fresh(&self, path: &Path) -> bool {
+        fs::metadata(path)
+            .and_then(|metadata| metadata.modified())
+            .ok()
+            .and_then(|modified| SystemTime::now().duration_since(modified).ok())
+            .map(|age| age < self.max_age)
+            .unwrap_or(false)
+    }
+
+    fn store(&self, url: &str, mut payload: impl Read, validators: &Validators) -> Result<()> {
+        fs::create_dir_all(&self.dir).map_err(|err| io_error(&self.dir, err))?;
+
+        // The validators of the previous payload must not outlive it, or a
+        // failed download would have the server confirm the stale guide.
+        let meta_path = self.meta_path(url);
+        match fs::remove_file(&meta_path) {
+            Err(err) if err.kind() != io::ErrorKind::NotFound => {
+                return Err(io_error(&meta_path, err));
+            }
+            _ => {}
+        }
+
+        // Download next to the payload and swap it in once complete, so an
+        // interrupted transfer never leaves a truncated guide behind.
+        let payload_path = self.payload_path(url);
+        let partial_path = payload_path.with_extension("part");
+        File::create(&partial_path)
+            .and_then(|mut file| io::copy(&mut payload, &mut file))
+            .map_err(|err| io_error(&partial_path, err))?;
+        fs::rename(&partial_path, &payload_path).map_err(|err| io_error(&payload_path, err))?;
+
+        let mut meta = String::new();
+        if let Some(etag) = &validators.etag {
+            meta.push_str(&format!("etag: {}\n", etag));
+        }
+        if let Some(last_modified) = &validators.last_modified {
+            meta.push_str(&format!("last-modified: {}\n", last_modified));
+        }
+        fs::write(&meta_path, meta).map_err(|err| io_error(&meta_path, err))
+    }
+
+    fn read_validators(&self, url: &str) -> Result<Validators> {
+        let mut validators = Validators::default();
+        let meta_path = self.meta_path(url);
+        let meta = match self.read_file(&meta_path)? {
+            Some(meta) => String::from_utf8_lossy(&meta).into_owned(),
+            None => return Ok(validators),
+        };
+
+        for line in meta.lines() {
+            match line.split_once(": ") {
+                Some(("etag", value)) => validators.etag = Some(value.to_owned()),
+                Some(("last-modified", value)) => validators.last_modified = Some(value.to_owned()),
+                _ => {}
+            }
+        }
+
+        Ok(validators)
+    }
+
+    fn read_file(&self, path: &Path) -> Result<Option<Vec<u8>>> {
+        match fs::read(path) {
+            Ok(bytes) => Ok(Some(bytes)),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(io_error(path, err)),
+        }
+    }
+
+    fn touch(&self, path: &Path) -> Result<()> {
+        File::options()
+            .append(true)
+            .open(path)
+            .and_then(|file| file.set_modified(SystemTime::now()))
+            .map_err(|err| io_error(path, err))
+    }
+
+    fn payload_path(&self, url: &str) -> PathBuf {
+        self.dir.join(format!("{}.data", cache_key(url)))
+    }
+
+    fn meta_path(&self, url: &str) -> PathBuf {
+        self.dir.join(format!("{}.meta", cache_key(url)))
+    }
+}
+
+/// The file name the payload of `url` is cached under: the start of the URL,
+/// to tell the files apart, and a hash of the whole of it, so that URLs that
+/// read the same once sanitised do not share a file.
+fn cache_key(url: &str) -> String {
+    let name = url
+        .trim_start_matches("https://")
+        .trim_start_matches("http://");
+    let name: String = name.chars().take(64).collect();
+    format!("{}-{:016x}", sanitize(&name), fnv1a(url))
+}
+
+/// Replaces every character of `text` but ASCII letters, digits, `.` and `-`
+/// with `_`, so that it can be used in file names and identifiers.
+pub fn sanitize(text: &str) -> String {
+    text.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '.' || c == '-' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+/// The 64-bit FNV-1a hash of `text`, which unlike the hasher of the standard
+/// library stays the same across Rust releases.
+fn fnv1a(text: &str) -> u64 {
+    text.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+fn header(res: &reqwest::blocking::Response, name: reqwest::header::HeaderName) -> Option<String> {
+    res.headers()
+        .get(name)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_owned)
+}
+
+fn io_error(path: &Path, source: io::Error) -> TvprogError {
+    TvprogError::Io {
+        location: path.display().to_string(),
+        source,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::mpsc::Receiver;
+
+    use super::*;
+    use crate::fixtures::{response, serve};
+
+    /// A cache in a directory of its own, named after the test.
+    fn cache(name: &str, max_age: Duration) -> Cache {
+        let dir =
+            std::env::temp_dir().join(format!("tvprog-cache-{}-{}", std::process::id(), name));
+        let _ = fs::remove_dir_all(&dir);
+        Cache {
+            max_age,
+            ..Cache::new(dir)
+        }
+    }
+
+    fn guide(body: &str, headers: &[&str]) -> Vec<u8> {
+        response("200 OK", headers, body.as_bytes())
+    }
+
+    fn fetched(cache: &Cache, url: &str) -> String {
+        fs::read_to_string(cache.fetch(url).unwrap()).unwrap()
+    }
+
+    fn next_request(requests: &Receiver<String>) -> String {
+        requests.recv().unwrap().to_ascii_lowercase()
+    }
+
+    #[test]
+    fn serves_fresh_copies_without_asking_the_server() {
+        let (url, requests) = serve(vec![guide("<tv/>", &["ETag: \"v1\""])]);
+        let cache = cache("fresh", DEFAULT_MAX_AGE);
+
+        assert_eq!(fetched(&cache, &url), "<tv/>");
+        assert!(!next_request(&requests).contains("if-none-match"));
+        // The server answers once only: a second request would fail.
+        assert_eq!(fetched(&cache, &url), "<tv/>");
+        assert_eq!(
+            fs::read_to_string(cache.meta_path(&url)).unwrap(),
+            "etag: \"v1\"\n"
+        );
+        fs::remove_dir_all(&cache.dir).unwrap();
+    }
+
+    #[test]
+    fn revalidates_stale_copies_with_their_validators() {
+        let last_modified = "Last-Modified: Tue, 20 Oct 2026 06:00:00 GMT";
+        let (url, requests) = serve(vec![
+            guide("<tv/>", &["ETag: \"v1\"", last_modified]),
+            response("304 Not Modified", &[], b""),
+            guide("<tv></tv>", &["ETag: \"v2\""]),
+        ]);
+        let cache = cache("stale", Duration::ZERO);
+
+        assert_eq!(fetched(&cache, &url), "<tv/>");
+        next_request(&requests);
+        assert_eq!(fetched(&cache, &url), "<tv/>");
+        let request = next_request(&requests);
+        assert!(request.contains("if-none-match: \"v1\"\r\n"));
+        assert!(request.contains("if-modified-since: tue, 20 oct 2026 06:00:00 gmt\r\n"));
+
+        assert_eq!(fetched(&cache, &url), "<tv></tv>");
+        assert_eq!(
+            fs::read_to_string(cache.meta_path(&url)).unwrap(),
+            "etag: \"v2\"\n"
+        );
+        fs::remove_dir_all(&cache.dir).unwrap();
+    }
+
+    #[test]
+    fn drops_the_validators_of_a_payload_being_replaced() {
+        let mut truncated = guide("<tv></tv>", &["ETag: \"v2\""]);
+        truncated.truncate(truncated.len() - 4);
+        let (url, requests) = serve(vec![
+            guide("<tv/>", &["ETag: \"v1\""]),
+            truncated,
+            guide("<tv></tv>", &[]),
+        ]);
+        let cache = cache("replaced", Duration::ZERO);
+
+        assert_eq!(fetched(&cache, &url), "<tv/>");
+        next_request(&requests);
+        assert!(cache.fetch(&url).is_err());
+        assert!(next_request(&requests).contains("if-none-match: \"v1\""));
+        // The failed download left the old guide without its validators, so
+        // the server is not asked to confirm it.
+        assert!(!cache.meta_path(&url).exists());
+        assert_eq!(fetched(&cache, &url), "<tv></tv>");
+        assert!(!next_request(&requests).contains("if-none-match"));
+        fs::remove_dir_all(&cache.dir).unwrap();
+    }
+
+    #[test]
+    fn works_offline_from_the_cached_copies_only() {
+        let (url, _requests) = serve(vec![guide("<tv/>", &[])]);
+        let online = cache("offline", Duration::ZERO);
+        let offline = Cache {
+            offline: true,
+            ..online.clone()
+        };
+
+        assert!(matches!(
+            offline.fetch(&url),
+            Err(TvprogError::NotCached(cached)) if cached == url
+        ));
+        assert_eq!(fetched(&online, &url), "<tv/>");
+        // Stale, but the server is not asked.
+        assert_eq!(fetched(&offline, &url), "<tv/>");
+        fs::remove_dir_all(&online.dir).unwrap();
+    }
+
+    #[test]
+    fn keys_every_url_apart() {
+        let key = cache_key("http://example.org/a?b");
+        assert_ne!(key, cache_key("https://example.org/a_b"));
+        assert_ne!(key, cache_key("https://example.org/a?b"));
+        assert!(key.starts_with("example.org_a_b-"));
+        assert_eq!(key, cache_key("http://example.org/a?b"));
+        assert_eq!(sanitize("tf1.fr/Émission 1"), "tf1.fr__mission_1");
+    }
+}
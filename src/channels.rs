@@ -0,0 +1,152 @@
+//! Named lists of channels to keep.
+
+use crate::error::{Result, TvprogError};
+use crate::search::fold;
+
+/// A named list of channel display names.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Preset {
+    pub name: &'static str,
+    pub channels: &'static [&'static str],
+}
+
+/// The national entertainment channels of the TNT, news channels aside.
+pub const TNT_NATIONAL: &[&str] = &[
+    "TF1",
+    "France 2",
+    "France 3",
+    "Canal+",
+    "France 5",
+    "M6",
+    "Arte",
+    "C8",
+    "W9",
+    "TMC",
+    "TFX",
+    "NRJ 12",
+    "France 4",
+    "CSTAR",
+    "L'Equipe",
+    "6ter",
+    "RMC Story",
+    "RMC Découverte",
+    "Chérie 25",
+];
+
+/// Numbers of the national TNT channels.
+pub const TNT_NUMBERS: &[(u32, &str)] = &[
+    (1, "TF1"),
+    (2, "France 2"),
+    (3, "France 3"),
+    (4, "Canal+"),
+    (5, "France 5"),
+    (6, "M6"),
+    (7, "Arte"),
+    (8, "C8"),
+    (9, "W9"),
+    (10, "TMC"),
+    (11, "TFX"),
+    (12, "NRJ 12"),
+    (13, "LCP"),
+    (14, "France 4"),
+    (15, "BFM TV"),
+    (16, "CNews"),
+    (17, "CSTAR"),
+    (18, "Gulli"),
+    (20, "TF1 Séries Films"),
+    (21, "L'Equipe"),
+    (22, "6ter"),
+    (23, "RMC Story"),
+    (24, "RMC Découverte"),
+    (25, "Chérie 25"),
+    (26, "LCI"),
+    (27, "franceinfo"),
+];
+
+/// Every national channel of the TNT, in channel number order.
+pub const TNT_FULL: &[&str] = &tnt_names();
+
+const fn tnt_names() -> [&'static str; TNT_NUMBERS.len()] {
+    let mut names = [""; TNT_NUMBERS.len()];
+    let mut index = 0;
+    while index < names.len() {
+        names[index] = TNT_NUMBERS[index].1;
+        index += 1;
+    }
+    names
+}
+
+/// Returns the TNT number of the channel displayed as `name`, ignoring case,
+/// accents, spaces and punctuation, so `France2` is number 2.
+pub fn channel_number(name: &str) -> Option<u32> {
+    let name = channel_key(name);
+    TNT_NUMBERS
+        .iter()
+        .find(|(_, channel)| channel_key(channel) == name)
+        .map(|(number, _)| *number)
+}
+
+fn channel_key(name: &str) -> String {
+    fold(name)
+        .chars()
+        .filter(|c| c.is_alphanumeric() || *c == '+')
+        .collect()
+}
+
+/// Every preset, selectable by name.
+pub const PRESETS: &[Preset] = &[
+    Preset {
+        name: "tnt-national",
+        channels: TNT_NATIONAL,
+    },
+    Preset {
+        name: "tnt-full",
+        channels: TNT_FULL,
+    },
+];
+
+/// The preset used when no channel is chosen.
+pub const DEFAULT_PRESET: &str = "tnt-national";
+
+/// Returns the preset called `name`.
+pub fn preset(name: &str) -> Result<&'static Preset> {
+    PRESETS
+        .iter()
+        .find(|preset| preset.name == name)
+        .ok_or_else(|| TvprogError::UnknownPreset(name.to_owned()))
+}
+
+/// Returns the channels of the preset called `name`.
+pub fn preset_channels(name: &str) -> Result<Vec<String>> {
+    Ok(preset(name)?
+        .channels
+        .iter()
+        .map(|channel| channel.to_string())
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn numbers_channels_whatever_their_spelling() {
+        assert_eq!(channel_number("France 2"), Some(2));
+        assert_eq!(channel_number("France2"), Some(2));
+        assert_eq!(channel_number("FRANCE-2"), Some(2));
+        assert_eq!(channel_number("Canal +"), Some(4));
+        assert_eq!(channel_number("l'équipe"), Some(21));
+        assert_eq!(channel_number("RMC Decouverte"), Some(24));
+        assert_eq!(channel_number("France"), None);
+        assert_eq!(channel_number("Canal"), None);
+    }
+
+    #[test]
+    fn lists_the_full_tnt_in_number_order() {
+        assert_eq!(TNT_FULL.len(), TNT_NUMBERS.len());
+        assert_eq!(TNT_FULL[..3], ["TF1", "France 2", "France 3"]);
+        assert!(TNT_NATIONAL.iter().all(|name| TNT_FULL.contains(name)));
+        assert_eq!(preset_channels(DEFAULT_PRESET).unwrap()[0], "TF1");
+        assert!(matches!(preset("tnt"), Err(TvprogError::UnknownPreset(_))));
+    }
+}
@@ -0,0 +1,261 @@
+//! Transparent decompression of gzip, xz and zip guides.
+
+use std::fmt;
+use std::io::{self, BufRead, BufReader, Cursor, Read};
+
+use flate2::read::MultiGzDecoder;
+use xz2::read::XzDecoder;
+use zip::ZipArchive;
+
+use crate::error::{Result, TvprogError};
+
+/// Container formats guides are commonly published in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Compression {
+    Gzip,
+    Xz,
+    Zip,
+}
+
+impl Compression {
+    /// Recognizes the format from the first bytes of the payload.
+    pub fn from_magic(bytes: &[u8]) -> Option<Self> {
+        if bytes.starts_with(&[0x1f, 0x8b]) {
+            Some(Compression::Gzip)
+        } else if bytes.starts_with(&[0xfd, b'7', b'z', b'X', b'Z', 0x00]) {
+            Some(Compression::Xz)
+        } else if bytes.starts_with(b"PK\x03\x04") {
+            Some(Compression::Zip)
+        } else {
+            None
+        }
+    }
+
+    fn decoder(self, mut input: Box<dyn BufRead>) -> io::Result<Box<dyn BufRead>> {
+        match self {
+            Compression::Gzip => Ok(Box::new(Decompressed {
+                inner: BufReader::new(MultiGzDecoder::new(input)),
+                compression: self,
+            })),
+            Compression::Xz => Ok(Box::new(Decompressed {
+                inner: BufReader::new(XzDecoder::new(input)),
+                compression: self,
+            })),
+            Compression::Zip => {
+                // Zip archives keep their index at the end, so they have to be
+                // fully buffered before the guide can be extracted.
+                let mut bytes = Vec::new();
+                input.read_to_end(&mut bytes)?;
+                let mut archive = ZipArchive::new(Cursor::new(bytes)).map_err(io::Error::other)?;
+                let xml_name = archive
+                    .file_names()
+                    .find(|name| name.ends_with(".xml"))
+                    .map(str::to_owned);
+                let mut entry = match xml_name {
+                    Some(name) => archive.by_name(&name),
+                    None => archive.by_index(0),
+                }
+                .map_err(io::Error::other)?;
+                let mut decoded = Vec::new();
+                entry.read_to_end(&mut decoded)?;
+                Ok(Box::new(Cursor::new(decoded)))
+            }
+        }
+    }
+}
+
+impl fmt::Display for Compression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Compression::Gzip => write!(f, "gzip"),
+            Compression::Xz => write!(f, "xz"),
+            Compression::Zip => write!(f, "zip"),
+        }
+    }
+}
+
+/// Wraps `input` in a decompressor when its magic bytes tell it is
+/// compressed. Plain XML is returned as is, whatever its `name`.
+pub fn decompress(mut input: Box<dyn BufRead>, name: &str) -> Result<Box<dyn BufRead>> {
+    let magic = input.fill_buf().map_err(|source| TvprogError::Io {
+        location: name.to_owned(),
+        source,
+    })?;
+    let compression = match Compression::from_magic(magic) {
+        Some(compression) => compression,
+        None => return Ok(input),
+    };
+
+    compression
+        .decoder(input)
+        .map_err(|source| TvprogError::Decompress {
+            compression,
+            source,
+        })
+}
+
+/// A stream decompressed on the fly. Its read errors carry a
+/// [`DecompressError`], so that they are told apart from XML errors once they
+/// come out of the XML reader.
+struct Decompressed<R> {
+    inner: R,
+    compression: Compression,
+}
+
+impl<R: BufRead> Read for Decompressed<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let compression = self.compression;
+        self.inner
+            .read(buf)
+            .map_err(|err| DecompressError::wrap(compression, err))
+    }
+}
+
+impl<R: BufRead> BufRead for Decompressed<R> {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        let compression = self.compression;
+        self.inner
+            .fill_buf()
+            .map_err(|err| DecompressError::wrap(compression, err))
+    }
+
+    fn consume(&mut self, amount: usize) {
+        self.inner.consume(amount)
+    }
+}
+
+/// A failure of the decompressor while the guide is being read.
+#[derive(Debug)]
+pub struct DecompressError {
+    pub compression: Compression,
+    pub source: io::Error,
+}
+
+impl DecompressError {
+    fn wrap(compression: Compression, source: io::Error) -> io::Error {
+        io::Error::new(
+            source.kind(),
+            DecompressError {
+                compression,
+                source,
+            },
+        )
+    }
+
+    /// Takes the decompressor failure out of `err`, or gives `err` back when
+    /// it has another cause.
+    pub fn take(err: io::Error) -> std::result::Result<Self, io::Error> {
+        if !err
+            .get_ref()
+            .is_some_and(|inner| inner.is::<DecompressError>())
+        {
+            return Err(err);
+        }
+        let kind = err.kind();
+        match err.into_inner() {
+            Some(inner) => inner
+                .downcast::<DecompressError>()
+                .map(|err| *err)
+                .map_err(|inner| io::Error::new(kind, inner)),
+            None => Err(io::Error::from(kind)),
+        }
+    }
+}
+
+impl fmt::Display for DecompressError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.source.fmt(f)
+    }
+}
+
+impl std::error::Error for DecompressError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.source)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use flate2::write::GzEncoder;
+    use xz2::write::XzEncoder;
+
+    use super::*;
+    use crate::fixtures::GUIDE;
+    use crate::xmltv::{Item, XmltvReader};
+
+    fn gzip(bytes: &[u8]) -> Vec<u8> {
+        let mut encoder = GzEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(bytes).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    fn xz(bytes: &[u8]) -> Vec<u8> {
+        let mut encoder = XzEncoder::new(Vec::new(), 6);
+        encoder.write_all(bytes).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    fn read(bytes: Vec<u8>, name: &str) -> Result<Vec<Item>> {
+        let input = decompress(Box::new(Cursor::new(bytes)), name)?;
+        XmltvReader::new(input).collect()
+    }
+
+    #[test]
+    fn recognizes_formats_from_their_magic_bytes() {
+        assert_eq!(Compression::from_magic(&gzip(b"")), Some(Compression::Gzip));
+        assert_eq!(Compression::from_magic(&xz(b"")), Some(Compression::Xz));
+        assert_eq!(
+            Compression::from_magic(b"PK\x03\x04rest"),
+            Some(Compression::Zip)
+        );
+        assert_eq!(Compression::from_magic(GUIDE.as_bytes()), None);
+        assert_eq!(Compression::from_magic(b""), None);
+    }
+
+    #[test]
+    fn decompresses_whatever_the_name() {
+        let plain = read(GUIDE.as_bytes().to_vec(), "-").unwrap();
+        assert_eq!(read(gzip(GUIDE.as_bytes()), "tnt.xml").unwrap(), plain);
+        assert_eq!(read(xz(GUIDE.as_bytes()), "-").unwrap(), plain);
+        // A plain guide saved under a compressed name is read as is.
+        assert_eq!(
+            read(GUIDE.as_bytes().to_vec(), "tnt.xml.gz").unwrap(),
+            plain
+        );
+        assert_eq!(
+            read(GUIDE.as_bytes().to_vec(), "tnt.xml.xz").unwrap(),
+            plain
+        );
+    }
+
+    #[test]
+    fn reports_streaming_failures_as_decompression_errors() {
+        let mut truncated = gzip(GUIDE.as_bytes());
+        truncated.truncate(truncated.len() / 2);
+        assert!(matches!(
+            read(truncated, "tnt.xml.gz"),
+            Err(TvprogError::Decompress {
+                compression: Compression::Gzip,
+                ..
+            })
+        ));
+
+        let mut truncated = xz(GUIDE.as_bytes());
+        truncated.truncate(truncated.len() / 2);
+        assert!(matches!(
+            read(truncated, "tnt.xml.xz"),
+            Err(TvprogError::Decompress {
+                compression: Compression::Xz,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn keeps_xml_errors_apart() {
+        let broken = gzip(b"<tv><channel id=\"a\"></programme></tv>");
+        assert!(matches!(read(broken, "-"), Err(TvprogError::Decode(_))));
+    }
+}
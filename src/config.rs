@@ -0,0 +1,798 @@
+//! User configuration.
+//!
+//! Settings are merged from several layers, each one overriding the previous:
+//!
+//! 1. the built-in defaults,
+//! 2. the top level of the configuration file, `~/.config/tvprog/config.toml`
+//!    unless `--config` or `TVPROG_CONFIG` points elsewhere,
+//! 3. the `[profiles.<name>]` table of that file selected with `--profile` or
+//!    `TVPROG_PROFILE`,
+//! 4. the `TVPROG_*` environment variables, e.g. `TVPROG_SOURCE` or
+//!    `TVPROG_CHANNELS` or `TVPROG_CATEGORIES` (comma-separated),
+//! 5. the command-line flags.
+//!
+//! Within a layer, `channels` wins over `preset`; across layers, the highest
+//! one setting either of them decides the channel list. Likewise, a `slot`
+//! discards the `from`, `to` and `min_duration` set by the layers below it,
+//! and turning `offline` or `no_cache` on discards the other one below. A
+//! single layer cannot turn both on.
+
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use serde::Deserialize;
+
+use crate::cache::{Cache, DEFAULT_MAX_AGE};
+use crate::channels::{preset_channels, DEFAULT_PRESET};
+use crate::delimited::DEFAULT_COLUMNS;
+use crate::duration::{format_duration, parse_duration};
+use crate::error::{Result, TvprogError};
+use crate::filter::{Filter, Strictness};
+use crate::genre::Category;
+use crate::html::HtmlLayout;
+use crate::ics::Calendar;
+use crate::output::{Format, Output};
+use crate::render::TableLayout;
+use crate::sort::SortKey;
+use crate::source::Source;
+use crate::time::{parse_date, parse_time, parse_timezone, today_in, DateRange, DEFAULT_TIMEZONE};
+use crate::window::{slot, Overlap, Window};
+
+/// Languages preferred when none is configured.
+pub const DEFAULT_LANG: &str = "fr";
+
+/// One layer of settings. Every field is optional: unset ones are taken from
+/// the layers below.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct Layer {
+    pub source: Option<String>,
+    pub cache_dir: Option<PathBuf>,
+    pub no_cache: Option<bool>,
+    pub max_age: Option<String>,
+    pub offline: Option<bool>,
+    pub lenient: Option<bool>,
+    pub preset: Option<String>,
+    pub channels: Option<Vec<String>>,
+    pub categories: Option<Vec<String>>,
+    pub exclude_categories: Option<Vec<String>>,
+    pub lang: Option<String>,
+    pub timezone: Option<String>,
+    pub date: Option<String>,
+    pub days: Option<u32>,
+    pub slot: Option<String>,
+    pub from: Option<String>,
+    pub to: Option<String>,
+    pub min_duration: Option<String>,
+    pub overlap: Option<String>,
+    pub sort: Option<String>,
+    pub group: Option<String>,
+    pub format: Option<String>,
+    pub columns: Option<Vec<String>>,
+    pub alarm: Option<String>,
+    pub html_layout: Option<String>,
+    pub channel_width: Option<u32>,
+    pub title_width: Option<u32>,
+}
+
+impl Layer {
+    /// Returns `self` overridden by every setting of `over`.
+    pub fn merge(self, over: Layer) -> Layer {
+        // A slot chosen above replaces the bounds set below.
+        let below = match over.slot {
+            Some(_) => Layer {
+                from: None,
+                to: None,
+                min_duration: None,
+                ..self
+            },
+            None => self,
+        };
+        // Working offline reads the cache that bypassing it skips, so turning
+        // either on above turns the other off below.
+        let below = match (over.offline, over.no_cache) {
+            (Some(true), _) => Layer {
+                no_cache: None,
+                ..below
+            },
+            (_, Some(true)) => Layer {
+                offline: None,
+                ..below
+            },
+            _ => below,
+        };
+        let (preset, channels) = if over.preset.is_some() || over.channels.is_some() {
+            (over.preset, over.channels)
+        } else {
+            (below.preset, below.channels)
+        };
+
+        Layer {
+            source: over.source.or(below.source),
+            cache_dir: over.cache_dir.or(below.cache_dir),
+            no_cache: over.no_cache.or(below.no_cache),
+            max_age: over.max_age.or(below.max_age),
+            offline: over.offline.or(below.offline),
+            lenient: over.lenient.or(below.lenient),
+            preset,
+            channels,
+            categories: over.categories.or(below.categories),
+            exclude_categories: over.exclude_categories.or(below.exclude_categories),
+            lang: over.lang.or(below.lang),
+            timezone: over.timezone.or(below.timezone),
+            date: over.date.or(below.date),
+            days: over.days.or(below.days),
+            slot: over.slot.or(below.slot),
+            from: over.from.or(below.from),
+            to: over.to.or(below.to),
+            min_duration: over.min_duration.or(below.min_duration),
+            overlap: over.overlap.or(below.overlap),
+            sort: over.sort.or(below.sort),
+            group: over.group.or(below.group),
+            format: over.format.or(below.format),
+            columns: over.columns.or(below.columns),
+            alarm: over.alarm.or(below.alarm),
+            html_layout: over.html_layout.or(below.html_layout),
+            channel_width: over.channel_width.or(below.channel_width),
+            title_width: over.title_width.or(below.title_width),
+        }
+    }
+
+    /// Reads the `TVPROG_*` environment variables.
+    pub fn from_env() -> Result<Layer> {
+        Ok(Layer {
+            source: env_var("TVPROG_SOURCE"),
+            cache_dir: env_var("TVPROG_CACHE_DIR").map(PathBuf::from),
+            no_cache: env_bool("TVPROG_NO_CACHE")?,
+            max_age: env_var("TVPROG_MAX_AGE"),
+            offline: env_bool("TVPROG_OFFLINE")?,
+            lenient: env_bool("TVPROG_LENIENT")?,
+            preset: env_var("TVPROG_PRESET"),
+            channels: env_list("TVPROG_CHANNELS"),
+            categories: env_list("TVPROG_CATEGORIES"),
+            exclude_categories: env_list("TVPROG_EXCLUDE_CATEGORIES"),
+            lang: env_var("TVPROG_LANG"),
+            timezone: env_var("TVPROG_TIMEZONE"),
+            date: env_var("TVPROG_DATE"),
+            days: env_number("TVPROG_DAYS")?,
+            slot: env_var("TVPROG_SLOT"),
+            from: env_var("TVPROG_FROM"),
+            to: env_var("TVPROG_TO"),
+            min_duration: env_var("TVPROG_MIN_DURATION"),
+            overlap: env_var("TVPROG_OVERLAP"),
+            sort: env_var("TVPROG_SORT"),
+            group: env_var("TVPROG_GROUP"),
+            format: env_var("TVPROG_FORMAT"),
+            columns: env_list("TVPROG_COLUMNS"),
+            alarm: env_var("TVPROG_ALARM"),
+            html_layout: env_var("TVPROG_HTML_LAYOUT"),
+            channel_width: env_number("TVPROG_CHANNEL_WIDTH")?,
+            title_width: env_number("TVPROG_TITLE_WIDTH")?,
+        })
+    }
+}
+
+fn env_var(name: &str) -> Option<String> {
+    env::var(name).ok().filter(|value| !value.is_empty())
+}
+
+fn env_list(name: &str) -> Option<Vec<String>> {
+    env_var(name).map(|list| {
+        list.split(',')
+            .map(str::trim)
+            .filter(|item| !item.is_empty())
+            .map(str::to_owned)
+            .collect()
+    })
+}
+
+fn env_bool(name: &str) -> Result<Option<bool>> {
+    match env_var(name) {
+        None => Ok(None),
+        Some(value) => match value.to_ascii_lowercase().as_str() {
+            "1" | "true" | "yes" | "on" => Ok(Some(true)),
+            "0" | "false" | "no" | "off" => Ok(Some(false)),
+            _ => Err(TvprogError::InvalidEnv {
+                name: name.to_owned(),
+                value,
+            }),
+        },
+    }
+}
+
+fn env_number(name: &str) -> Result<Option<u32>> {
+    env_var(name)
+        .map(|value| {
+            value.parse().map_err(|_| TvprogError::InvalidEnv {
+                name: name.to_owned(),
+                value,
+            })
+        })
+        .transpose()
+}
+
+/// The configuration file: top-level settings and named profiles.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Config {
+    pub settings: Layer,
+    pub profiles: BTreeMap<String, Layer>,
+}
+
+impl Config {
+    /// `$XDG_CONFIG_HOME/tvprog/config.toml`, or its platform equivalent.
+    pub fn default_path() -> Option<PathBuf> {
+        dirs::config_dir().map(|dir| dir.join("tvprog").join("config.toml"))
+    }
+
+    /// Reads the configuration file at `path`.
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(path).map_err(|source| TvprogError::Io {
+            location: path.display().to_string(),
+            source,
+        })?;
+        Config::parse(&content).map_err(|err| TvprogError::Config {
+            path: path.to_owned(),
+            message: err.to_string(),
+        })
+    }
+
+    /// Reads the configuration file at the [default path](Config::default_path),
+    /// falling back to an empty configuration when there is none.
+    pub fn load_default() -> Result<Self> {
+        match Config::default_path() {
+            Some(path) => match Config::load(&path) {
+                Err(TvprogError::Io { source, .. }) if source.kind() == io::ErrorKind::NotFound => {
+                    Ok(Config::default())
+                }
+                config => config,
+            },
+            None => Ok(Config::default()),
+        }
+    }
+
+    fn parse(content: &str) -> std::result::Result<Self, toml::de::Error> {
+        let mut table: toml::Table = toml::from_str(content)?;
+        let profiles = match table.remove("profiles") {
+            Some(profiles) => profiles.try_into()?,
+            None => BTreeMap::new(),
+        };
+        let settings = toml::Value::Table(table).try_into()?;
+
+        Ok(Config { settings, profiles })
+    }
+
+    /// The top-level settings overridden by the profile called `name`.
+    pub fn layer(&self, profile: Option<&str>) -> Result<Layer> {
+        match profile {
+            None => Ok(self.settings.clone()),
+            Some(name) => {
+                let profile = self
+                    .profiles
+                    .get(name)
+                    .ok_or_else(|| TvprogError::UnknownProfile(name.to_owned()))?;
+                Ok(self.settings.clone().merge(profile.clone()))
+            }
+        }
+    }
+
+    /// Every layer above the defaults merged in order: the top-level
+    /// settings, the profile called `profile`, the `env` and the `cli` ones.
+    pub fn merged(&self, profile: Option<&str>, env: Layer, cli: Layer) -> Result<Layer> {
+        Ok(self.layer(profile)?.merge(env).merge(cli))
+    }
+}
+
+/// The effective settings, once every layer is merged and parsed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Settings {
+    pub source: Source,
+    pub cache_dir: Option<PathBuf>,
+    pub no_cache: bool,
+    pub max_age: Duration,
+    pub offline: bool,
+    pub lenient: bool,
+    pub preset: Option<String>,
+    pub slot: Option<String>,
+    pub filter: Filter,
+    /// Order of the programmes, when set; each command has its own default.
+    pub sort: Option<SortKey>,
+    pub output: Output,
+}
+
+impl Settings {
+    /// Parses a merged layer, filling the gaps with the defaults.
+    pub fn resolve(layer: Layer) -> Result<Self> {
+        let (preset, channels) = match layer.channels {
+            Some(channels) => (None, channels),
+            None => {
+                let preset = layer.preset.unwrap_or_else(|| DEFAULT_PRESET.to_owned());
+                let channels = preset_channels(&preset)?;
+                (Some(preset), channels)
+            }
+        };
+        let timezone = match &layer.timezone {
+            Some(timezone) => parse_timezone(timezone)?,
+            None => DEFAULT_TIMEZONE,
+        };
+        let cache_dir = layer.cache_dir.or_else(Cache::default_dir);
+        let no_cache = layer.no_cache.unwrap_or(false);
+        let offline = layer.offline.unwrap_or(false);
+        if offline && (no_cache || cache_dir.is_none()) {
+            return Err(TvprogError::OfflineWithoutCache);
+        }
+        let today = today_in(timezone);
+        let dates = DateRange {
+            first: match &layer.date {
+                Some(date) => parse_date(date, today)?,
+                None => today,
+            },
+            days: match layer.days.unwrap_or(1) {
+                0 => return Err(TvprogError::NoDays),
+                days => days,
+            },
+        };
+        let default_window = match &layer.slot {
+            Some(name) => slot(name)?.window,
+            None => Window::default(),
+        };
+        let default_layout = TableLayout::default();
+
+        Ok(Settings {
+            source: match layer.source {
+                Some(source) => source.parse()?,
+                None => Source::default(),
+            },
+            cache_dir,
+            no_cache,
+            max_age: match layer.max_age {
+                Some(max_age) => parse_duration(&max_age)?,
+                None => DEFAULT_MAX_AGE,
+            },
+            offline,
+            lenient: layer.lenient.unwrap_or(false),
+            preset,
+            slot: layer.slot,
+            filter: Filter {
+                channels,
+                categories: parse_categories(layer.categories),
+                excluded_categories: parse_categories(layer.exclude_categories),
+                languages: layer
+                    .lang
+                    .as_deref()
+                    .unwrap_or(DEFAULT_LANG)
+                    .parse()
+                    .unwrap(),
+                dates,
+                window: Window {
+                    from: layer
+                        .from
+                        .as_deref()
+                        .map(parse_time)
+                        .transpose()?
+                        .unwrap_or(default_window.from),
+                    to: layer
+                        .to
+                        .as_deref()
+                        .map(parse_time)
+                        .transpose()?
+                        .unwrap_or(default_window.to),
+                    min_duration: layer
+                        .min_duration
+                        .as_deref()
+                        .map(parse_duration)
+                        .transpose()?
+                        .unwrap_or(default_window.min_duration),
+                },
+                overlap: match layer.overlap {
+                    Some(overlap) => overlap.parse()?,
+                    None => Overlap::default(),
+                },
+                timezone,
+            },
+            sort: layer.sort.as_deref().map(str::parse).transpose()?,
+            output: Output {
+                format: match layer.format {
+                    Some(format) => format.parse()?,
+                    None => Format::default(),
+                },
+                layout: TableLayout {
+                    channel_width: layer.channel_width.unwrap_or(default_layout.channel_width),
+                    title_width: layer.title_width.unwrap_or(default_layout.title_width),
+                },
+                group: layer.group.as_deref().map(str::parse).transpose()?,
+                columns: match layer.columns {
+                    Some(columns) => columns
+                        .iter()
+                        .map(|column| column.parse())
+                        .collect::<Result<_>>()?,
+                    None => DEFAULT_COLUMNS.to_vec(),
+                },
+                calendar: Calendar {
+                    timezone,
+                    alarm: layer.alarm.as_deref().map(parse_duration).transpose()?,
+                },
+                html_layout: match layer.html_layout {
+                    Some(html_layout) => html_layout.parse()?,
+                    None => HtmlLayout::default(),
+                },
+            },
+        })
+    }
+
+    /// The cache to go through, unless caching is disabled.
+    pub fn cache(&self) -> Option<Cache> {
+        if self.no_cache {
+            return None;
+        }
+        self.cache_dir.as_ref().map(|dir| Cache {
+            max_age: self.max_age,
+            offline: self.offline,
+            ..Cache::new(dir)
+        })
+    }
+
+    pub fn strictness(&self) -> Strictness {
+        match self.lenient {
+            true => Strictness::Lenient,
+            false => Strictness::Strict,
+        }
+    }
+
+    /// Writes the settings back as a configuration file.
+    pub fn to_toml(&self) -> String {
+        let mut table = toml::Table::new();
+        let mut set = |key: &str, value: toml::Value| {
+            table.insert(key.to_owned(), value);
+        };
+
+        set("source", self.source.to_string().into());
+        if let Some(cache_dir) = &self.cache_dir {
+            set("cache_dir", cache_dir.display().to_string().into());
+        }
+        set("no_cache", self.no_cache.into());
+        set("max_age", format_duration(self.max_age).into());
+        set("offline", self.offline.into());
+        set("lenient", self.lenient.into());
+        // The channels of a layer win over its preset, so only one is written.
+        match &self.preset {
+            Some(preset) => set("preset", preset.as_str().into()),
+            None => set("channels", self.filter.channels.clone().into()),
+        }
+        set("categories", format_categories(&self.filter.categories));
+        set(
+            "exclude_categories",
+            format_categories(&self.filter.excluded_categories),
+        );
+        set("lang", self.filter.languages.to_string().into());
+        set("timezone", self.filter.timezone.name().into());
+        set("date", self.filter.dates.first.to_string().into());
+        set("days", i64::from(self.filter.dates.days).into());
+        if let Some(slot) = &self.slot {
+            set("slot", slot.as_str().into());
+        }
+        set(
+            "from",
+            self.filter.window.from.format("%H:%M").to_string().into(),
+        );
+        set(
+            "to",
+            self.filter.window.to.format("%H:%M").to_string().into(),
+        );
+        set(
+            "min_duration",
+            format_duration(self.filter.window.min_duration).into(),
+        );
+        set("overlap", self.filter.overlap.to_string().into());
+        if let Some(sort) = self.sort {
+            set("sort", sort.to_string().into());
+        }
+        if let Some(group) = self.output.group {
+            set("group", group.to_string().into());
+        }
+        set("format", self.output.format.to_string().into());
+        set(
+            "columns",
+            self.output
+                .columns
+                .iter()
+                .map(|column| column.to_string())
+                .collect::<Vec<_>>()
+                .into(),
+        );
+        if let Some(alarm) = self.output.calendar.alarm {
+            set("alarm", format_duration(alarm).into());
+        }
+        set("html_layout", self.output.html_layout.to_string().into());
+        set(
+            "channel_width",
+            i64::from(self.output.layout.channel_width).into(),
+        );
+        set(
+            "title_width",
+            i64::from(self.output.layout.title_width).into(),
+        );
+
+        toml::to_string(&table).unwrap_or_default()
+    }
+}
+
+fn parse_categories(categories: Option<Vec<String>>) -> Vec<Category> {
+    categories
+        .unwrap_or_default()
+        .iter()
+        .map(|category| category.parse().unwrap())
+        .collect()
+}
+
+fn format_categories(categories: &[Category]) -> toml::Value {
+    categories
+        .iter()
+        .map(|category| category.to_string())
+        .collect::<Vec<_>>()
+        .into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn layer(offline: Option<bool>, no_cache: Option<bool>) -> Layer {
+        Layer {
+            cache_dir: Some(PathBuf::from("/var/cache/tvprog")),
+            offline,
+            no_cache,
+            ..Layer::default()
+        }
+    }
+
+    #[test]
+    fn turns_off_the_cache_setting_below_the_one_turned_on() {
+        let offline = layer(None, Some(true)).merge(layer(Some(true), None));
+        let cache = Settings::resolve(offline).unwrap().cache().unwrap();
+        assert!(cache.offline);
+        assert_eq!(cache.dir, PathBuf::from("/var/cache/tvprog"));
+
+        let no_cache = layer(Some(true), None).merge(layer(None, Some(true)));
+        let settings = Settings::resolve(no_cache).unwrap();
+        assert!(!settings.offline);
+        assert!(settings.cache().is_none());
+    }
+
+    #[test]
+    fn rejects_working_offline_without_the_cache() {
+        assert!(matches!(
+            Settings::resolve(layer(Some(true), Some(true))),
+            Err(TvprogError::OfflineWithoutCache)
+        ));
+        // Turning either off keeps the other.
+        let offline = layer(Some(true), Some(true)).merge(layer(None, Some(false)));
+        assert!(Settings::resolve(offline).unwrap().cache().unwrap().offline);
+    }
+
+    #[test]
+    fn rejects_listing_no_days() {
+        let days = |days| {
+            Settings::resolve(Layer {
+                days,
+                ..Layer::default()
+            })
+        };
+        assert!(matches!(days(Some(0)), Err(TvprogError::NoDays)));
+        assert_eq!(days(None).unwrap().filter.dates.days, 1);
+        assert_eq!(days(Some(7)).unwrap().filter.dates.days, 7);
+    }
+
+    const CONFIG: &str = r#"
+source = "https://example.org/tnt.xml"
+lang = "fr"
+days = 2
+format = "json"
+slot = "nuit"
+
+[profiles.sport]
+lang = "en"
+days = 3
+categories = ["sport"]
+"#;
+
+    fn strings(values: &[&str]) -> Option<Vec<String>> {
+        Some(values.iter().map(|value| value.to_string()).collect())
+    }
+
+    #[test]
+    fn merges_each_setting_over_the_one_below() {
+        let below = Layer {
+            source: Some("below.xml".to_owned()),
+            lang: Some("fr".to_owned()),
+            days: Some(2),
+            ..Layer::default()
+        };
+        let over = Layer {
+            lang: Some("en".to_owned()),
+            days: Some(3),
+            ..Layer::default()
+        };
+        let merged = below.merge(over);
+        assert_eq!(merged.source.as_deref(), Some("below.xml"));
+        assert_eq!(merged.lang.as_deref(), Some("en"));
+        assert_eq!(merged.days, Some(3));
+    }
+
+    #[test]
+    fn a_slot_discards_the_bounds_set_below() {
+        let bounds = Layer {
+            from: Some("20:00".to_owned()),
+            to: Some("21:00".to_owned()),
+            min_duration: Some("10m".to_owned()),
+            ..Layer::default()
+        };
+        let slot = Layer {
+            slot: Some("nuit".to_owned()),
+            ..Layer::default()
+        };
+
+        let merged = bounds.clone().merge(slot.clone());
+        assert_eq!(merged.slot.as_deref(), Some("nuit"));
+        assert_eq!(
+            (merged.from, merged.to, merged.min_duration),
+            (None, None, None)
+        );
+
+        // Bounds set next to the slot or above it refine it.
+        let refined = Layer {
+            to: Some("02:00".to_owned()),
+            ..slot.clone()
+        };
+        assert_eq!(Layer::default().merge(refined).to.as_deref(), Some("02:00"));
+        let merged = slot.merge(bounds);
+        assert_eq!(merged.slot.as_deref(), Some("nuit"));
+        assert_eq!(merged.from.as_deref(), Some("20:00"));
+    }
+
+    #[test]
+    fn the_highest_layer_choosing_channels_decides_them() {
+        let preset = Layer {
+            preset: Some("tnt-full".to_owned()),
+            ..Layer::default()
+        };
+        let channels = Layer {
+            channels: strings(&["TF1"]),
+            ..Layer::default()
+        };
+
+        let merged = preset.clone().merge(channels.clone());
+        assert_eq!((merged.preset, merged.channels), (None, strings(&["TF1"])));
+        let merged = channels.clone().merge(preset);
+        assert_eq!(
+            (merged.preset.as_deref(), merged.channels),
+            (Some("tnt-full"), None)
+        );
+
+        // Within a layer, the channels win over the preset.
+        let both = Layer {
+            preset: Some("tnt-full".to_owned()),
+            ..channels
+        };
+        let settings = Settings::resolve(both).unwrap();
+        assert_eq!(settings.preset, None);
+        assert_eq!(settings.filter.channels, ["TF1"]);
+    }
+
+    #[test]
+    fn selects_a_profile_over_the_top_level_settings() {
+        let config = Config::parse(CONFIG).unwrap();
+
+        let top = config.layer(None).unwrap();
+        assert_eq!((top.lang.as_deref(), top.days), (Some("fr"), Some(2)));
+        assert_eq!(top.categories, None);
+
+        let sport = config.layer(Some("sport")).unwrap();
+        assert_eq!(sport.source.as_deref(), Some("https://example.org/tnt.xml"));
+        assert_eq!((sport.lang.as_deref(), sport.days), (Some("en"), Some(3)));
+        assert_eq!(sport.categories, strings(&["sport"]));
+
+        assert!(matches!(
+            config.layer(Some("cinema")),
+            Err(TvprogError::UnknownProfile(name)) if name == "cinema"
+        ));
+        assert!(Config::parse("colour = \"blue\"").is_err());
+    }
+
+    #[test]
+    fn follows_the_documented_precedence() {
+        let config = Config::parse(CONFIG).unwrap();
+        let env = Layer {
+            days: Some(4),
+            format: Some("csv".to_owned()),
+            ..Layer::default()
+        };
+        let cli = Layer {
+            format: Some("html".to_owned()),
+            ..Layer::default()
+        };
+
+        let layer = config.merged(Some("sport"), env, cli).unwrap();
+        let settings = Settings::resolve(layer).unwrap();
+        // Defaults, file, profile, environment, command line.
+        assert_eq!(settings.max_age, DEFAULT_MAX_AGE);
+        assert_eq!(
+            settings.source,
+            Source::Http("https://example.org/tnt.xml".to_owned())
+        );
+        assert_eq!(settings.filter.languages.to_string(), "en");
+        assert_eq!(settings.filter.dates.days, 4);
+        assert_eq!(settings.output.format, Format::Html);
+    }
+
+    #[test]
+    fn reads_the_environment() {
+        let vars = [
+            ("TVPROG_SOURCE", ""),
+            ("TVPROG_CHANNELS", " TF1, ,France 2 "),
+            ("TVPROG_OFFLINE", "yes"),
+            ("TVPROG_LENIENT", "0"),
+            ("TVPROG_DAYS", "3"),
+        ];
+        for (name, value) in vars {
+            env::set_var(name, value);
+        }
+        let layer = Layer::from_env();
+        env::set_var("TVPROG_DAYS", "three");
+        let invalid = Layer::from_env();
+        for (name, _) in vars {
+            env::remove_var(name);
+        }
+
+        let layer = layer.unwrap();
+        assert_eq!(layer.source, None);
+        assert_eq!(layer.channels, strings(&["TF1", "France 2"]));
+        assert_eq!((layer.offline, layer.lenient), (Some(true), Some(false)));
+        assert_eq!(layer.days, Some(3));
+        assert!(matches!(
+            invalid,
+            Err(TvprogError::InvalidEnv { name, value }) if name == "TVPROG_DAYS" && value == "three"
+        ));
+    }
+
+    #[test]
+    fn writes_settings_that_read_back_the_same() {
+        let layer = Config::parse(
+            r#"
+preset = "tnt-full"
+categories = ["film", "policier"]
+lang = "fr,en"
+timezone = "America/Montreal"
+date = "2026-10-20"
+days = 3
+slot = "nuit"
+to = "02:00"
+overlap = "during"
+sort = "channel"
+group = "channel"
+format = "ics"
+alarm = "15m"
+html_layout = "grid"
+columns = ["start", "title"]
+"#,
+        )
+        .unwrap()
+        .settings;
+        let settings = Settings::resolve(layer).unwrap();
+
+        let read_back = |settings: &Settings| {
+            Settings::resolve(Config::parse(&settings.to_toml()).unwrap().settings).unwrap()
+        };
+        assert_eq!(read_back(&settings), settings);
+
+        let channels = Settings::resolve(Layer {
+            channels: strings(&["TF1", "Arte"]),
+            ..Layer::default()
+        })
+        .unwrap();
+        assert_eq!(read_back(&channels), channels);
+    }
+}
@@ -0,0 +1,213 @@
+//! CSV and TSV rendering of the selected programmes, for spreadsheets.
+
+use std::fmt;
+use std::io::{self, Write};
+use std::str::FromStr;
+
+use crate::error::{Result, TvprogError};
+use crate::program::Program;
+
+/// Layout of the `start` and `end` columns, read as a date by spreadsheets.
+const DATE_TIME_FORMAT: &str = "%Y-%m-%d %H:%M";
+
+/// A column of the CSV and TSV outputs.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Column {
+    /// Display name of the channel.
+    Channel,
+    /// XMLTV id of the channel.
+    ChannelId,
+    /// Start date and time, e.g. `2026-10-20 21:05`.
+    Start,
+    /// End date and time.
+    End,
+    /// Minutes on air.
+    Duration,
+    Title,
+    SubTitle,
+    Description,
+    /// Category labels of the provider, comma-separated.
+    Category,
+    /// Normalised genres, comma-separated.
+    Genre,
+    /// Episode number, e.g. `S03E12`.
+    Episode,
+}
+
+/// Columns written when none are chosen.
+pub const DEFAULT_COLUMNS: &[Column] = &[
+    Column::Channel,
+    Column::Start,
+    Column::End,
+    Column::Title,
+    Column::Category,
+];
+
+const COLUMNS: &[(Column, &str)] = &[
+    (Column::Channel, "channel"),
+    (Column::ChannelId, "channel_id"),
+    (Column::Start, "start"),
+    (Column::End, "end"),
+    (Column::Duration, "duration"),
+    (Column::Title, "title"),
+    (Column::SubTitle, "sub_title"),
+    (Column::Description, "description"),
+    (Column::Category, "category"),
+    (Column::Genre, "genre"),
+    (Column::Episode, "episode"),
+];
+
+impl Column {
+    /// Name of the column, as written in the header row.
+    pub fn name(self) -> &'static str {
+        COLUMNS
+            .iter()
+            .find(|(column, _)| *column == self)
+            .map(|(_, name)| *name)
+            .unwrap_or_default()
+    }
+
+    /// The value of the column for `program`.
+    pub fn value(self, program: &Program) -> String {
+        match self {
+            Column::Channel => program.channel.clone(),
+            Column::ChannelId => program.channel_id.clone(),
+            Column::Start => program.start.format(DATE_TIME_FORMAT).to_string(),
+            Column::End => program.end.format(DATE_TIME_FORMAT).to_string(),
+            Column::Duration => (program.end - program.start).num_minutes().to_string(),
+            Column::Title => program.title.clone(),
+            Column::SubTitle => program.sub_title.clone().unwrap_or_default(),
+            Column::Description => program.description.clone().unwrap_or_default(),
+            Column::Category => program.categories.join(", "),
+            Column::Genre => program
+                .genres()
+                .iter()
+                .map(|genre| genre.name())
+                .collect::<Vec<_>>()
+                .join(", "),
+            Column::Episode => episode(program).unwrap_or_default(),
+        }
+    }
+}
+
+impl FromStr for Column {
+    type Err = TvprogError;
+
+    fn from_str(value: &str) -> Result<Self> {
+        let name = value.trim().to_lowercase().replace('-', "_");
+        COLUMNS
+            .iter()
+            .find(|(_, column)| *column == name)
+            .map(|(column, _)| *column)
+            .ok_or_else(|| TvprogError::UnknownColumn(value.to_owned()))
+    }
+}
+
+impl fmt::Display for Column {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// The on-screen episode number, or one built from the `xmltv_ns` number.
+fn episode(program: &Program) -> Option<String> {
+    if let Some(onscreen) = program.onscreen_episode() {
+        return Some(onscreen.to_owned());
+    }
+    match program.season_episode()? {
+        (Some(season), Some(episode)) => Some(format!("S{:02}E{:02}", season, episode)),
+        (None, Some(episode)) => Some(format!("E{:02}", episode)),
+        (Some(season), None) => Some(format!("S{:02}", season)),
+        (None, None) => None,
+    }
+}
+
+/// Writes a header row then one row per programme, fields separated by
+/// `delimiter` and quoted when needed.
+pub fn write_delimited(
+    programs: &[Program],
+    columns: &[Column],
+    delimiter: u8,
+    out: &mut impl Write,
+) -> io::Result<()> {
+    let mut writer = csv::WriterBuilder::new()
+        .delimiter(delimiter)
+        .from_writer(out);
+    writer.write_record(columns.iter().map(|column| column.name()))?;
+    for program in programs {
+        writer.write_record(columns.iter().map(|column| column.value(program)))?;
+    }
+    writer.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{programs, GUIDE};
+
+    const COLUMNS: [Column; 4] = [
+        Column::Channel,
+        Column::Title,
+        Column::SubTitle,
+        Column::Description,
+    ];
+
+    fn written(delimiter: u8) -> String {
+        let mut out = Vec::new();
+        write_delimited(&programs(GUIDE), &COLUMNS, delimiter, &mut out).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn quotes_fields_holding_delimiters_quotes_or_newlines() {
+        let csv = written(b',');
+        let lines: Vec<&str> = csv.lines().collect();
+
+        assert_eq!(lines[0], "channel,title,sub_title,description");
+        assert_eq!(
+            lines[1],
+            "TF1,\"Koh-Lanta, la légende\",,\"Les \"\"aventuriers\"\" reviennent & repartent.\""
+        );
+        assert!(lines[2].starts_with(
+            "France 2,Columbo | *Meurtre* [inédit],\"Un \"\"témoin\"\", enfin\",\"Première ligne"
+        ));
+    }
+
+    #[test]
+    fn reads_back_as_written() {
+        for delimiter in [b',', b'\t'] {
+            let written = written(delimiter);
+            let mut reader = csv::ReaderBuilder::new()
+                .delimiter(delimiter)
+                .from_reader(written.as_bytes());
+            let rows: Vec<csv::StringRecord> = reader
+                .records()
+                .collect::<std::result::Result<_, _>>()
+                .unwrap();
+
+            assert_eq!(rows.len(), 2);
+            assert_eq!(&rows[1][1], "Columbo | *Meurtre* [inédit]");
+            assert_eq!(&rows[1][2], "Un \"témoin\", enfin");
+            assert_eq!(&rows[1][3], "Première ligne\nseconde ligne");
+        }
+    }
+
+    #[test]
+    fn builds_episode_numbers() {
+        let programs = programs(GUIDE);
+        assert_eq!(Column::Episode.value(&programs[0]), "S02E05");
+        assert_eq!(Column::Episode.value(&programs[1]), "");
+        assert_eq!(Column::Genre.value(&programs[0]), "jeu, telerealite");
+        assert_eq!(Column::Duration.value(&programs[1]), "120");
+    }
+
+    #[test]
+    fn parses_column_names() {
+        assert_eq!("sub-title".parse::<Column>().unwrap(), Column::SubTitle);
+        assert_eq!(" Channel_ID ".parse::<Column>().unwrap(), Column::ChannelId);
+        assert!(matches!(
+            "rating".parse::<Column>(),
+            Err(TvprogError::UnknownColumn(_))
+        ));
+    }
+}
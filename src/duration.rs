@@ -0,0 +1,122 @@
+//! Human-friendly durations such as `30m`, `2h` or `1h30m`.
+
+use std::time::Duration;
+
+use crate::error::{Result, TvprogError};
+
+/// Parses a duration made of `<number><unit>` pairs, the units being `d`,
+/// `h`, `m` and `s`. A bare number counts minutes.
+pub fn parse_duration(value: &str) -> Result<Duration> {
+    let invalid = || TvprogError::InvalidDuration(value.to_owned());
+    let value = value.trim();
+    if value.is_empty() {
+        return Err(invalid());
+    }
+    if let Ok(minutes) = value.parse::<u64>() {
+        let seconds = minutes.checked_mul(60).ok_or_else(invalid)?;
+        return Ok(Duration::from_secs(seconds));
+    }
+
+    let mut seconds: u64 = 0;
+    let mut number = String::new();
+    for c in value.chars() {
+        if c.is_ascii_digit() {
+            number.push(c);
+            continue;
+        }
+        let amount: u64 = number.parse().map_err(|_| invalid())?;
+        let unit = match c {
+            'd' => 86_400,
+            'h' => 3_600,
+            'm' => 60,
+            's' => 1,
+            _ => return Err(invalid()),
+        };
+        seconds = amount
+            .checked_mul(unit)
+            .and_then(|amount| seconds.checked_add(amount))
+            .ok_or_else(invalid)?;
+        number.clear();
+    }
+    if !number.is_empty() {
+        return Err(invalid());
+    }
+
+    Ok(Duration::from_secs(seconds))
+}
+
+/// Writes a duration the way [`parse_duration`] reads it, e.g. `1h30m`.
+pub fn format_duration(duration: Duration) -> String {
+    let mut seconds = duration.as_secs();
+    let mut formatted = String::new();
+    for (unit, length) in [('d', 86_400), ('h', 3_600), ('m', 60), ('s', 1)] {
+        if seconds >= length {
+            formatted.push_str(&format!("{}{}", seconds / length, unit));
+            seconds %= length;
+        }
+    }
+    if formatted.is_empty() {
+        formatted.push_str("0s");
+    }
+    formatted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn minutes(minutes: u64) -> Duration {
+        Duration::from_secs(minutes * 60)
+    }
+
+    #[test]
+    fn parses_units_and_bare_minutes() {
+        assert_eq!(parse_duration("30m").unwrap(), minutes(30));
+        assert_eq!(parse_duration("2h").unwrap(), minutes(120));
+        assert_eq!(parse_duration("1h30m").unwrap(), minutes(90));
+        assert_eq!(parse_duration(" 90 ").unwrap(), minutes(90));
+        assert_eq!(parse_duration("1d2h").unwrap(), minutes(26 * 60));
+        assert_eq!(parse_duration("45s").unwrap(), Duration::from_secs(45));
+        assert_eq!(parse_duration("0").unwrap(), Duration::ZERO);
+    }
+
+    #[test]
+    fn rejects_malformed_durations() {
+        for value in ["", "m", "1x", "1h30", "h1", "-5m", "1.5h"] {
+            assert!(
+                matches!(parse_duration(value), Err(TvprogError::InvalidDuration(_))),
+                "{:?} was accepted",
+                value
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_durations_that_overflow() {
+        for value in [
+            "999999999999999999m",
+            "307445734561825861",
+            "99999999999999999d",
+            "18446744073709551615s1s",
+        ] {
+            assert!(
+                matches!(parse_duration(value), Err(TvprogError::InvalidDuration(_))),
+                "{:?} was accepted",
+                value
+            );
+        }
+    }
+
+    #[test]
+    fn formats_what_it_parses() {
+        assert_eq!(format_duration(Duration::ZERO), "0s");
+        assert_eq!(format_duration(minutes(90)), "1h30m");
+        assert_eq!(
+            format_duration(minutes(26 * 60) + Duration::from_secs(5)),
+            "1d2h5s"
+        );
+        for value in ["35m", "1h30m", "2d", "1d1h1m1s"] {
+            assert_eq!(format_duration(parse_duration(value).unwrap()), value);
+        }
+    }
+}
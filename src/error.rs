@@ -0,0 +1,201 @@
+//! Errors raised along the guide pipeline.
+
+use std::fmt;
+use std::path::PathBuf;
+
+use crate::compression::{Compression, DecompressError};
+
+/// Everything that can go wrong while loading and reading a guide.
+#[derive(Debug)]
+pub enum TvprogError {
+    /// The guide could not be downloaded.
+    Fetch(reqwest::Error),
+    /// A guide or cache file could not be read or written.
+    Io {
+        location: String,
+        source: std::io::Error,
+    },
+    /// The source is neither an http(s) URL, a `file://` URL, a path nor `-`.
+    UnsupportedSource(String),
+    /// `--offline` was requested but the guide has never been cached.
+    NotCached(String),
+    /// `--offline` was requested with the cache disabled or without a cache
+    /// directory.
+    OfflineWithoutCache,
+    /// A duration is not written like `30m`, `2h` or `1h30m`.
+    InvalidDuration(String),
+    /// A compressed guide could not be unpacked.
+    Decompress {
+        compression: Compression,
+        source: std::io::Error,
+    },
+    /// The configuration file is not valid TOML or has unknown settings.
+    Config { path: PathBuf, message: String },
+    /// No channel preset has this name.
+    UnknownPreset(String),
+    /// The configuration file has no profile with this name.
+    UnknownProfile(String),
+    /// A time of day is not written like `20:45` or `20h45`.
+    InvalidTime(String),
+    /// A date is neither ISO, French, relative nor a weekday name.
+    InvalidDate(String),
+    /// The number of days to list is 0.
+    NoDays,
+    /// The tz database has no time zone with this name.
+    UnknownTimezone(String),
+    /// A setting read from the environment has an invalid value.
+    InvalidEnv { name: String, value: String },
+    /// No time slot has this name.
+    UnknownSlot(String),
+    /// An overlap mode is neither `starts`, `during` nor `ends`.
+    UnknownOverlap(String),
+    /// Programmes cannot be sorted on this key.
+    UnknownSortKey(String),
+    /// Programmes cannot be grouped on this key.
+    UnknownGrouping(String),
+    /// Programmes cannot be written in this format.
+    UnknownFormat(String),
+    /// The CSV and TSV outputs have no column with this name.
+    UnknownColumn(String),
+    /// The HTML output has no layout with this name.
+    UnknownHtmlLayout(String),
+    /// The guide is not a well-formed XMLTV document.
+    Decode(quick_xml::Error),
+    /// A `start` or `stop` attribute is not an XMLTV timestamp.
+    Timestamp {
+        value: String,
+        source: chrono::ParseError,
+    },
+    /// A search pattern is not a valid regular expression.
+    InvalidRegex(regex::Error),
+    /// A programme refers to a channel the guide does not declare.
+    UnknownChannel(String),
+    /// Some programmes could not be read; every failure is kept.
+    InvalidProgrammes(Vec<TvprogError>),
+}
+
+/// Shorthand for results carrying a [`TvprogError`].
+pub type Result<T> = std::result::Result<T, TvprogError>;
+
+impl fmt::Display for TvprogError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TvprogError::Fetch(err) => write!(f, "could not fetch the guide: {}", err),
+            TvprogError::Io { location, source } => {
+                write!(f, "could not read {}: {}", location, source)
+            }
+            TvprogError::UnsupportedSource(value) => write!(f, "unsupported source {:?}", value),
+            TvprogError::NotCached(url) => write!(f, "{} is not in the cache", url),
+            TvprogError::OfflineWithoutCache => write!(
+                f,
+                "working offline needs the cache, which is disabled or has no directory"
+            ),
+            TvprogError::InvalidDuration(value) => write!(f, "invalid duration {:?}", value),
+            TvprogError::Decompress {
+                compression,
+                source,
+            } => write!(
+                f,
+                "could not decompress the {} guide: {}",
+                compression, source
+            ),
+            TvprogError::Config { path, message } => {
+                write!(
+                    f,
+                    "invalid configuration in {}: {}",
+                    path.display(),
+                    message
+                )
+            }
+            TvprogError::UnknownPreset(name) => write!(f, "unknown channel preset {:?}", name),
+            TvprogError::UnknownProfile(name) => write!(f, "unknown profile {:?}", name),
+            TvprogError::InvalidTime(value) => write!(f, "invalid time {:?}", value),
+            TvprogError::InvalidDate(value) => write!(f, "invalid date {:?}", value),
+            TvprogError::NoDays => write!(f, "the number of days to list must be at least 1"),
+            TvprogError::UnknownTimezone(name) => write!(f, "unknown time zone {:?}", name),
+            TvprogError::InvalidEnv { name, value } => {
+                write!(f, "invalid value {:?} for {}", value, name)
+            }
+            TvprogError::UnknownSlot(name) => write!(f, "unknown time slot {:?}", name),
+            TvprogError::UnknownOverlap(name) => write!(f, "unknown overlap mode {:?}", name),
+            TvprogError::UnknownSortKey(name) => write!(f, "unknown sort key {:?}", name),
+            TvprogError::UnknownGrouping(name) => write!(f, "unknown grouping {:?}", name),
+            TvprogError::UnknownFormat(name) => write!(f, "unknown output format {:?}", name),
+            TvprogError::UnknownColumn(name) => write!(f, "unknown column {:?}", name),
+            TvprogError::UnknownHtmlLayout(name) => write!(f, "unknown HTML layout {:?}", name),
+            TvprogError::Decode(err) => write!(f, "could not decode the guide: {}", err),
+            TvprogError::Timestamp { value, source } => {
+                write!(f, "invalid timestamp {:?}: {}", value, source)
+            }
+            TvprogError::InvalidRegex(err) => write!(f, "invalid search pattern: {}", err),
+            TvprogError::UnknownChannel(id) => write!(f, "unknown channel {:?}", id),
+            TvprogError::InvalidProgrammes(errors) => {
+                write!(f, "{} programme(s) could not be read", errors.len())?;
+                for error in errors {
+                    write!(f, "\n  - {}", error)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl std::error::Error for TvprogError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            TvprogError::Fetch(err) => Some(err),
+            TvprogError::Io { source, .. } => Some(source),
+            TvprogError::Decompress { source, .. } => Some(source),
+            TvprogError::Decode(err) => Some(err),
+            TvprogError::Timestamp { source, .. } => Some(source),
+            TvprogError::InvalidRegex(err) => Some(err),
+            TvprogError::UnsupportedSource(_)
+            | TvprogError::NotCached(_)
+            | TvprogError::OfflineWithoutCache
+            | TvprogError::InvalidDuration(_)
+            | TvprogError::Config { .. }
+            | TvprogError::UnknownPreset(_)
+            | TvprogError::UnknownProfile(_)
+            | TvprogError::UnknownSlot(_)
+            | TvprogError::UnknownOverlap(_)
+            | TvprogError::UnknownSortKey(_)
+            | TvprogError::UnknownGrouping(_)
+            | TvprogError::UnknownFormat(_)
+            | TvprogError::UnknownColumn(_)
+            | TvprogError::UnknownHtmlLayout(_)
+            | TvprogError::InvalidTime(_)
+            | TvprogError::InvalidDate(_)
+            | TvprogError::NoDays
+            | TvprogError::UnknownTimezone(_)
+            | TvprogError::InvalidEnv { .. }
+            | TvprogError::UnknownChannel(_)
+            | TvprogError::InvalidProgrammes(_) => None,
+        }
+    }
+}
+
+impl From<reqwest::Error> for TvprogError {
+    fn from(err: reqwest::Error) -> Self {
+        TvprogError::Fetch(err)
+    }
+}
+
+impl From<quick_xml::Error> for TvprogError {
+    /// Failures of the decompressor met by the XML reader stay
+    /// [`TvprogError::Decompress`] errors.
+    fn from(err: quick_xml::Error) -> Self {
+        match err {
+            quick_xml::Error::Io(err) => match DecompressError::take(err) {
+                Ok(DecompressError {
+                    compression,
+                    source,
+                }) => TvprogError::Decompress {
+                    compression,
+                    source,
+                },
+                Err(err) => TvprogError::Decode(quick_xml::Error::Io(err)),
+            },
+            err => TvprogError::Decode(err),
+        }
+    }
+}
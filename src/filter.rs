@@ -0,0 +1,341 @@
+//! Selection of the programmes to list.
+
+use chrono::{DateTime, FixedOffset, NaiveDate};
+use chrono_tz::Tz;
+
+use crate::channels::TNT_NATIONAL;
+use crate::error::{Result, TvprogError};
+use crate::genre::Category;
+use crate::lang::Languages;
+use crate::program::Program;
+use crate::time::{DateRange, DEFAULT_TIMEZONE};
+use crate::window::{Overlap, Window};
+use crate::xmltv::{Element, Item, XMLChannel, XMLProgram};
+
+/// What [`filter_programs`] keeps, and how it presents it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Filter {
+    /// Display names of the channels to keep.
+    pub channels: Vec<String>,
+    /// Categories the programmes need one of; empty to keep them all.
+    pub categories: Vec<Category>,
+    /// Categories the programmes must have none of.
+    pub excluded_categories: Vec<Category>,
+    /// Preferred languages for the texts.
+    pub languages: Languages,
+    /// Days whose time slot the programmes have to meet.
+    pub dates: DateRange,
+    /// Time slot the programmes have to meet.
+    pub window: Window,
+    /// How the programmes have to meet the time slot.
+    pub overlap: Overlap,
+    /// Time zone the dates and the time slot are read in.
+    pub timezone: Tz,
+}
+
+impl Default for Filter {
+    fn default() -> Self {
+        Filter {
+            channels: TNT_NATIONAL
+                .iter()
+                .map(|channel| channel.to_string())
+                .collect(),
+            categories: Vec::new(),
+            excluded_categories: Vec::new(),
+            languages: Languages::default(),
+            dates: DateRange::default(),
+            window: Window::default(),
+            overlap: Overlap::default(),
+            timezone: DEFAULT_TIMEZONE,
+        }
+    }
+}
+
+/// How programmes that cannot be read are dealt with.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Strictness {
+    /// List the other programmes, then fail with every programme-level error
+    /// collected.
+    #[default]
+    Strict,
+    /// Skip the faulty programmes.
+    Lenient,
+}
+
+/// Outcome of [`filter_programs`]: the selected channels and programmes, and
+/// the errors met on the way.
+#[derive(Debug, Default)]
+pub struct Selection {
+    /// The `<tv>` element of the guide, without its children.
+    pub tv: Element,
+    /// Channels of the filter found in the guide, in document order.
+    pub channels: Vec<XMLChannel>,
+    pub programs: Vec<Program>,
+    pub errors: Vec<TvprogError>,
+}
+
+impl Selection {
+    /// Takes the errors met out of the selection. In [`Strictness::Strict`]
+    /// mode they come back as one error, to be reported once the selected
+    /// programmes are written; in [`Strictness::Lenient`] mode they are
+    /// dropped.
+    pub fn take_errors(&mut self, strictness: Strictness) -> Result<()> {
+        let errors = std::mem::take(&mut self.errors);
+        match strictness == Strictness::Strict && !errors.is_empty() {
+            true => Err(TvprogError::InvalidProgrammes(errors)),
+            false => Ok(()),
+        }
+    }
+}
+
+/// Keeps the programmes of `filter`, in document order.
+///
+/// Programmes are filtered as they are read, so only the selected ones are
+/// kept in memory. Channels are expected before the programmes referring to
+/// them, as the XMLTV DTD mandates.
+pub fn filter_programs(
+    guide: impl IntoIterator<Item = Result<Item>>,
+    filter: &Filter,
+) -> Result<Selection> {
+    collect_programs(guide, filter, |program| {
+        match slot_day(&program.start, &program.end, filter) {
+            Some(day) => {
+                program.day = day;
+                true
+            }
+            None => false,
+        }
+    })
+}
+
+/// Keeps the programmes of the channels of `filter` for which `keep` holds,
+/// in document order. `keep` may update the programmes it keeps. Programmes
+/// on channels the guide does not declare are errors of the selection.
+pub fn collect_programs(
+    guide: impl IntoIterator<Item = Result<Item>>,
+    filter: &Filter,
+    mut keep: impl FnMut(&mut Program) -> bool,
+) -> Result<Selection> {
+    let mut channels: Vec<XMLChannel> = Vec::new();
+    let mut filtered_channel_ids: Vec<String> = Vec::new();
+    let mut selection = Selection::default();
+
+    for item in guide {
+        match item? {
+            Item::Tv(tv) => selection.tv = tv,
+            Item::Channel(channel) => {
+                if is_selected_channel(&channel, &filter.channels) {
+                    filtered_channel_ids.push(channel.id.to_owned());
+                    selection.channels.push(channel.clone());
+                }
+                channels.push(channel);
+            }
+            Item::Programme(program) => {
+                match select_program(*program, &filtered_channel_ids, &channels, filter) {
+                    Ok(Some(mut program)) => {
+                        if keep(&mut program) {
+                            selection.programs.push(program);
+                        }
+                    }
+                    Ok(None) => {}
+                    Err(err) => selection.errors.push(err),
+                }
+            }
+        }
+    }
+
+    Ok(selection)
+}
+
+fn select_program(
+    program: XMLProgram,
+    filtered_channel_ids: &[String],
+    channels: &[XMLChannel],
+    filter: &Filter,
+) -> Result<Option<Program>> {
+    let channel = find_channel(&program.channel, channels)?;
+    if !filtered_channel_ids.contains(&program.channel) {
+        return Ok(None);
+    }
+
+    let program = Program::from_xml(program, channel, &filter.languages, filter.timezone)?;
+    if !has_selected_categories(&program, filter) {
+        return Ok(None);
+    }
+
+    Ok(Some(program))
+}
+
+/// Tells whether a programme has one of the categories of `filter`, if any,
+/// and none of its excluded ones.
+pub fn has_selected_categories(program: &Program, filter: &Filter) -> bool {
+    let has = |category: &Category| category.matches(&program.categories);
+
+    (filter.categories.is_empty() || filter.categories.iter().any(has))
+        && !filter.excluded_categories.iter().any(has)
+}
+
+fn is_selected_channel(channel: &XMLChannel, names: &[String]) -> bool {
+    names.iter().any(|name| channel.is_named(name))
+}
+
+/// Returns the first day of `filter` whose time slot the programme meets, if
+/// it lasts longer than the minimum duration.
+///
+/// Only the days the programme may meet the slot of are looked at: from the
+/// day before it starts, for slots spanning midnight, to the day it ends.
+pub fn slot_day(
+    start: &DateTime<FixedOffset>,
+    end: &DateTime<FixedOffset>,
+    filter: &Filter,
+) -> Option<NaiveDate> {
+    let duration = end.signed_duration_since(*start);
+    let min_duration = i64::try_from(filter.window.min_duration.as_secs()).unwrap_or(i64::MAX);
+    if duration.num_seconds() <= min_duration {
+        return None;
+    }
+
+    let first = start.date_naive().pred_opt().unwrap_or(start.date_naive());
+    first
+        .iter_days()
+        .take_while(|date| *date <= end.date_naive())
+        .filter(|date| filter.dates.contains(*date))
+        .find(|date| {
+            let span = filter.window.span(*date, filter.timezone);
+            filter.overlap.matches((*start, *end), span)
+        })
+}
+
+/// Returns the channel with the given id.
+pub fn find_channel<'a>(channel_id: &str, channels: &'a [XMLChannel]) -> Result<&'a XMLChannel> {
+    channels
+        .iter()
+        .find(|channel| channel.id == channel_id)
+        .ok_or_else(|| TvprogError::UnknownChannel(channel_id.to_owned()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::GUIDE;
+    use crate::window::slot;
+    use crate::xmltv::XmltvReader;
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 10, day).unwrap()
+    }
+
+    fn filter() -> Filter {
+        Filter {
+            channels: vec!["TF1".to_owned(), "France 2".to_owned()],
+            dates: DateRange::day(date(20)),
+            ..Filter::default()
+        }
+    }
+
+    fn titles(xml: &str, filter: &Filter) -> Vec<String> {
+        filter_programs(XmltvReader::new(xml.as_bytes()), filter)
+            .unwrap()
+            .programs
+            .into_iter()
+            .map(|program| program.title)
+            .collect()
+    }
+
+    #[test]
+    fn selects_the_channels_and_slot_of_the_filter() {
+        assert_eq!(titles(GUIDE, &filter()), ["Koh-Lanta, la légende"]);
+
+        let france_2 = Filter {
+            channels: vec!["France2".to_owned()],
+            window: slot("nuit").unwrap().window,
+            ..filter()
+        };
+        assert_eq!(titles(GUIDE, &france_2), ["Columbo | *Meurtre* [inédit]"]);
+    }
+
+    #[test]
+    fn includes_programmes_starting_with_the_slot() {
+        let at_start = GUIDE.replace("20261020210000 +0200", "20261020204500 +0200");
+        assert_eq!(titles(&at_start, &filter()), ["Koh-Lanta, la légende"]);
+    }
+
+    #[test]
+    fn lists_programmes_under_the_day_of_their_slot() {
+        let night = Filter {
+            window: slot("nuit").unwrap().window,
+            ..filter()
+        };
+        let selection = filter_programs(XmltvReader::new(GUIDE.as_bytes()), &night).unwrap();
+        assert_eq!(selection.programs.len(), 1);
+        assert_eq!(selection.programs[0].start.date_naive(), date(20));
+        assert_eq!(selection.programs[0].day, date(20));
+
+        let after_midnight = GUIDE
+            .replace("20261020233000 +0200", "20261021003000 +0200")
+            .replace("20261021013000 +0200", "20261021023000 +0200");
+        let selection =
+            filter_programs(XmltvReader::new(after_midnight.as_bytes()), &night).unwrap();
+        assert_eq!(selection.programs[0].start.date_naive(), date(21));
+        assert_eq!(selection.programs[0].day, date(20));
+    }
+
+    #[test]
+    fn copes_with_huge_date_ranges() {
+        let years = Filter {
+            dates: DateRange {
+                first: date(1),
+                days: u32::MAX,
+            },
+            ..filter()
+        };
+        assert_eq!(titles(GUIDE, &years), ["Koh-Lanta, la légende"]);
+    }
+
+    #[test]
+    fn collects_programme_errors_and_keeps_the_others() {
+        let broken = GUIDE.replace("20261020233000 +0200", "tonight");
+        let mut selection =
+            collect_programs(XmltvReader::new(broken.as_bytes()), &filter(), |_| true).unwrap();
+
+        assert_eq!(selection.programs.len(), 1);
+        assert_eq!(selection.errors.len(), 1);
+        assert!(selection.take_errors(Strictness::Lenient).is_ok());
+
+        selection
+            .errors
+            .push(TvprogError::UnknownChannel("x".to_owned()));
+        assert!(matches!(
+            selection.take_errors(Strictness::Strict),
+            Err(TvprogError::InvalidProgrammes(errors)) if errors.len() == 1
+        ));
+        assert!(selection.errors.is_empty());
+    }
+
+    #[test]
+    fn ignores_programmes_on_unselected_channels() {
+        let tf1 = Filter {
+            channels: vec!["TF1".to_owned()],
+            ..filter()
+        };
+        let selection =
+            collect_programs(XmltvReader::new(GUIDE.as_bytes()), &tf1, |_| true).unwrap();
+
+        assert_eq!(selection.programs.len(), 1);
+        assert!(selection.errors.is_empty());
+    }
+
+    #[test]
+    fn reports_programmes_on_undeclared_channels() {
+        let undeclared = GUIDE.replace("channel=\"france2.fr\"", "channel=\"unknown\"");
+        let mut selection =
+            collect_programs(XmltvReader::new(undeclared.as_bytes()), &filter(), |_| true).unwrap();
+
+        assert_eq!(selection.programs.len(), 1);
+        assert!(matches!(
+            selection.take_errors(Strictness::Strict),
+            Err(TvprogError::InvalidProgrammes(errors))
+                if matches!(&errors[..], [TvprogError::UnknownChannel(id)] if id == "unknown")
+        ));
+    }
+}
@@ -0,0 +1,98 @@
+//! In-memory guides and a local HTTP server shared by the unit tests.
+
+use std::io::{Read, Write};
+use std::net::TcpListener;
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+
+use crate::filter::{collect_programs, Filter};
+use crate::lang::Languages;
+use crate::program::Program;
+use crate::xmltv::XmltvReader;
+
+/// A guide of two channels with extra display names, and programmes with
+/// texts in several languages and characters the outputs have to escape.
+pub const GUIDE: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE tv SYSTEM "xmltv.dtd">
+<tv source-info-name="Test" source-info-url="https://example.org/">
+  <!-- The channels come first, as the DTD mandates. -->
+  <channel id="tf1.fr">
+    <display-name lang="fr">TF1</display-name>
+    <display-name>TF 1</display-name>
+    <display-name>1</display-name>
+    <icon src="https://example.org/tf1.png"/>
+  </channel>
+  <channel id="france2.fr">
+    <display-name>France 2</display-name>
+    <display-name>France2</display-name>
+  </channel>
+  <programme start="20261020210000 +0200" stop="20261020225000 +0200" channel="tf1.fr">
+    <title lang="fr">Koh-Lanta, la légende</title>
+    <title lang="en-GB">Survivor</title>
+    <desc lang="fr">Les "aventuriers" reviennent &amp; repartent.</desc>
+    <category lang="fr">Jeu</category>
+    <category lang="fr">Téléréalité</category>
+    <episode-num system="xmltv_ns">1.4.</episode-num>
+    <new/>
+  </programme>
+  <programme start="20261020233000 +0200" stop="20261021013000 +0200" channel="france2.fr">
+    <title>Columbo | *Meurtre* [inédit]</title>
+    <sub-title><![CDATA[Un "témoin", enfin]]></sub-title>
+    <desc>Première ligne
+seconde ligne</desc>
+    <category>Série policière</category>
+  </programme>
+</tv>
+"#;
+
+/// Every programme of `xml` on TF1 and France 2, in document order, with
+/// its texts in French.
+pub fn programs(xml: &str) -> Vec<Program> {
+    let filter = Filter {
+        channels: vec!["TF1".to_owned(), "France 2".to_owned()],
+        languages: Languages(vec!["fr".to_owned()]),
+        ..Filter::default()
+    };
+    collect_programs(XmltvReader::new(xml.as_bytes()), &filter, |_| true)
+        .unwrap()
+        .programs
+}
+
+/// An HTTP response closing the connection, with the extra `headers`.
+pub fn response(status: &str, headers: &[&str], body: &[u8]) -> Vec<u8> {
+    let mut response = format!(
+        "HTTP/1.1 {}\r\nContent-Length: {}\r\nConnection: close\r\n",
+        status,
+        body.len()
+    );
+    for header in headers {
+        response.push_str(header);
+        response.push_str("\r\n");
+    }
+    response.push_str("\r\n");
+    let mut response = response.into_bytes();
+    response.extend_from_slice(body);
+    response
+}
+
+/// Answers the next connections to a local port with `responses`, one each.
+/// Returns the URL of the server and a receiver of the heads of the requests
+/// it got.
+pub fn serve(responses: Vec<Vec<u8>>) -> (String, Receiver<String>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let (sender, requests) = mpsc::channel();
+    thread::spawn(move || {
+        for response in responses {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut head = Vec::new();
+            let mut byte = [0];
+            while !head.ends_with(b"\r\n\r\n") && stream.read(&mut byte).unwrap() == 1 {
+                head.push(byte[0]);
+            }
+            let _ = sender.send(String::from_utf8(head).unwrap());
+            stream.write_all(&response).unwrap();
+        }
+    });
+    (url, requests)
+}
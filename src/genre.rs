@@ -0,0 +1,246 @@
+//! Genres, normalised from the category labels of the providers.
+
+use std::convert::Infallible;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::search::fold;
+
+/// A genre shared by the category labels of every provider.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Genre {
+    Film,
+    Telefilm,
+    Serie,
+    Magazine,
+    Documentaire,
+    Information,
+    Sport,
+    Divertissement,
+    Jeu,
+    TeleRealite,
+    Jeunesse,
+    Musique,
+    Spectacle,
+}
+
+/// Beginnings of the folded category labels, most specific first, and the
+/// genre they belong to. Every genre name is among them.
+const LABELS: &[(&str, Genre)] = &[
+    ("telefilm", Genre::Telefilm),
+    ("tv movie", Genre::Telefilm),
+    ("film", Genre::Film),
+    ("long metrage", Genre::Film),
+    ("court metrage", Genre::Film),
+    ("cinema", Genre::Film),
+    ("movie", Genre::Film),
+    ("serie", Genre::Serie),
+    ("feuilleton", Genre::Serie),
+    ("sitcom", Genre::Serie),
+    ("soap", Genre::Serie),
+    ("series", Genre::Serie),
+    ("magazine", Genre::Magazine),
+    ("emission", Genre::Magazine),
+    ("talk show", Genre::Magazine),
+    ("documentaire", Genre::Documentaire),
+    ("documentary", Genre::Documentaire),
+    ("information", Genre::Information),
+    ("journal", Genre::Information),
+    ("actualite", Genre::Information),
+    ("meteo", Genre::Information),
+    ("debat", Genre::Information),
+    ("politique", Genre::Information),
+    ("news", Genre::Information),
+    ("sport", Genre::Sport),
+    ("football", Genre::Sport),
+    ("rugby", Genre::Sport),
+    ("tennis", Genre::Sport),
+    ("cyclisme", Genre::Sport),
+    ("basket", Genre::Sport),
+    ("handball", Genre::Sport),
+    ("formule 1", Genre::Sport),
+    ("athletisme", Genre::Sport),
+    ("jeunesse", Genre::Jeunesse),
+    ("dessin anime", Genre::Jeunesse),
+    ("animation", Genre::Jeunesse),
+    ("children", Genre::Jeunesse),
+    ("kids", Genre::Jeunesse),
+    ("jeu", Genre::Jeu),
+    ("game show", Genre::Jeu),
+    ("quiz", Genre::Jeu),
+    ("tele-realite", Genre::TeleRealite),
+    ("telerealite", Genre::TeleRealite),
+    ("tele realite", Genre::TeleRealite),
+    ("reality", Genre::TeleRealite),
+    ("divertissement", Genre::Divertissement),
+    ("variete", Genre::Divertissement),
+    ("humour", Genre::Divertissement),
+    ("entertainment", Genre::Divertissement),
+    ("musique", Genre::Musique),
+    ("musical", Genre::Musique),
+    ("concert", Genre::Musique),
+    ("clip", Genre::Musique),
+    ("opera", Genre::Musique),
+    ("music", Genre::Musique),
+    ("spectacle", Genre::Spectacle),
+    ("theatre", Genre::Spectacle),
+    ("cirque", Genre::Spectacle),
+    ("danse", Genre::Spectacle),
+];
+
+impl Genre {
+    /// Name of the genre, as accepted on the command line.
+    pub fn name(self) -> &'static str {
+        match self {
+            Genre::Film => "film",
+            Genre::Telefilm => "telefilm",
+            Genre::Serie => "serie",
+            Genre::Magazine => "magazine",
+            Genre::Documentaire => "documentaire",
+            Genre::Information => "information",
+            Genre::Sport => "sport",
+            Genre::Divertissement => "divertissement",
+            Genre::Jeu => "jeu",
+            Genre::TeleRealite => "telerealite",
+            Genre::Jeunesse => "jeunesse",
+            Genre::Musique => "musique",
+            Genre::Spectacle => "spectacle",
+        }
+    }
+
+    /// The genre a category label such as `Série policière` or `Film :
+    /// comédie` belongs to, ignoring case and accents.
+    pub fn of(label: &str) -> Option<Genre> {
+        let label = fold(label.trim());
+        LABELS
+            .iter()
+            .find(|(prefix, _)| label.starts_with(prefix))
+            .map(|(_, genre)| *genre)
+    }
+
+    /// The genre called `name`, or one of its labels such as `documentary`,
+    /// ignoring case and accents. Unlike [`Genre::of`], the whole name has
+    /// to match.
+    pub fn named(name: &str) -> Option<Genre> {
+        let name = fold(name.trim());
+        LABELS
+            .iter()
+            .find(|(label, _)| *label == name)
+            .map(|(_, genre)| *genre)
+    }
+}
+
+impl fmt::Display for Genre {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// A category programmes are selected on: a genre, when named as such, or
+/// else any label containing a text, ignoring case and accents.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Category {
+    Genre(Genre),
+    Label(String),
+}
+
+impl Category {
+    /// Tells whether one of the category `labels` of a programme is this one.
+    pub fn matches(&self, labels: &[String]) -> bool {
+        match self {
+            Category::Genre(genre) => labels.iter().any(|label| Genre::of(label) == Some(*genre)),
+            Category::Label(text) => labels.iter().any(|label| fold(label).contains(text)),
+        }
+    }
+}
+
+impl FromStr for Category {
+    type Err = Infallible;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        Ok(match Genre::named(value) {
+            Some(genre) => Category::Genre(genre),
+            None => Category::Label(fold(value.trim())),
+        })
+    }
+}
+
+impl fmt::Display for Category {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Category::Genre(genre) => genre.fmt(f),
+            Category::Label(text) => f.write_str(text),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalises_provider_labels() {
+        assert_eq!(Genre::of("Série policière"), Some(Genre::Serie));
+        assert_eq!(Genre::of("Film : comédie"), Some(Genre::Film));
+        assert_eq!(Genre::of("  Long métrage"), Some(Genre::Film));
+        assert_eq!(Genre::of("Téléfilm dramatique"), Some(Genre::Telefilm));
+        assert_eq!(Genre::of("TV Movie"), Some(Genre::Telefilm));
+        assert_eq!(Genre::of("TÉLÉ-RÉALITÉ"), Some(Genre::TeleRealite));
+        assert_eq!(Genre::of("Jeunesse"), Some(Genre::Jeunesse));
+        assert_eq!(Genre::of("Jeu"), Some(Genre::Jeu));
+        assert_eq!(Genre::of("Football : Ligue 1"), Some(Genre::Sport));
+        assert_eq!(Genre::of("Journal"), Some(Genre::Information));
+        assert_eq!(Genre::of("Documentary"), Some(Genre::Documentaire));
+        assert_eq!(Genre::of("Inclassable"), None);
+        assert_eq!(Genre::of(""), None);
+    }
+
+    #[test]
+    fn reads_categories_as_genres_or_labels() {
+        assert_eq!(
+            "série".parse::<Category>().unwrap(),
+            Category::Genre(Genre::Serie)
+        );
+        assert_eq!(
+            " Policier ".parse::<Category>().unwrap(),
+            Category::Label("policier".to_owned())
+        );
+        assert_eq!(
+            "TÉLÉ-RÉALITÉ".parse::<Category>().unwrap(),
+            Category::Genre(Genre::TeleRealite)
+        );
+        assert_eq!(
+            "documentary".parse::<Category>().unwrap(),
+            Category::Genre(Genre::Documentaire)
+        );
+        // Only whole genre names make a genre, not what begins with one.
+        assert_eq!(
+            "Documentaire animalier".parse::<Category>().unwrap(),
+            Category::Label("documentaire animalier".to_owned())
+        );
+        assert_eq!(
+            "series".parse::<Category>().unwrap(),
+            Category::Genre(Genre::Serie)
+        );
+        assert_eq!(
+            "jeux".parse::<Category>().unwrap(),
+            Category::Label("jeux".to_owned())
+        );
+    }
+
+    #[test]
+    fn matches_any_label_of_a_programme() {
+        let labels = ["Magazine".to_owned(), "Série policière".to_owned()];
+        assert!(Category::Genre(Genre::Serie).matches(&labels));
+        assert!(!Category::Genre(Genre::Film).matches(&labels));
+        assert!("POLICIÈRE".parse::<Category>().unwrap().matches(&labels));
+        assert!(!"western".parse::<Category>().unwrap().matches(&labels));
+
+        let animals = ["Documentaire animalier".to_owned()];
+        let documentaries = ["Documentaire".to_owned(), "Documentaire société".to_owned()];
+        let animal = "documentaire animalier".parse::<Category>().unwrap();
+        assert!(animal.matches(&animals));
+        assert!(!animal.matches(&documentaries));
+        assert!(Category::Genre(Genre::Documentaire).matches(&animals));
+    }
+}
@@ -0,0 +1,449 @@
+//! Self-contained HTML rendering of the selected programmes, to publish a
+//! guide on an intranet.
+//!
+//! The page has its style sheet inline and no script. The channel logos are
+//! embedded as `data:` URIs by [`embed_icons`], so the page loads nothing
+//! from the network.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::io::{self, Read, Write};
+use std::str::FromStr;
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use chrono::{DateTime, Duration, FixedOffset, Timelike};
+
+use crate::cache::Cache;
+use crate::error::{Result, TvprogError};
+use crate::program::Program;
+use crate::render::{default_grouping, group_by_channel, group_by_day, split};
+use crate::sort::Grouping;
+use crate::source::Source;
+use crate::time::format_date;
+use crate::xmltv::escape;
+
+/// How the programmes are laid out on the page.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum HtmlLayout {
+    /// Tables like the terminal ones.
+    #[default]
+    Table,
+    /// One channel per row along a time line, one grid per day.
+    Grid,
+}
+
+impl FromStr for HtmlLayout {
+    type Err = TvprogError;
+
+    fn from_str(value: &str) -> Result<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "table" => Ok(HtmlLayout::Table),
+            "grid" | "grille" => Ok(HtmlLayout::Grid),
+            _ => Err(TvprogError::UnknownHtmlLayout(value.to_owned())),
+        }
+    }
+}
+
+impl fmt::Display for HtmlLayout {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            HtmlLayout::Table => "table",
+            HtmlLayout::Grid => "grid",
+        })
+    }
+}
+
+const STYLE: &str = "\
+body { font-family: system-ui, sans-serif; margin: 2em; color: #222; background: #fafafa; }
+h1 { font-size: 1.6em; }
+h2 { font-size: 1.2em; margin-top: 2em; text-transform: capitalize; }
+table { border-collapse: collapse; width: 100%; background: #fff; }
+th, td { border: 1px solid #ddd; padding: .4em .6em; text-align: left; vertical-align: middle; }
+th { background: #333; color: #fff; }
+tr[title] { cursor: help; }
+.channel { white-space: nowrap; }
+.channel img { height: 1.5em; width: auto; vertical-align: middle; margin-right: .4em; }
+.sub { display: block; color: #666; font-size: .9em; }
+.time { white-space: nowrap; font-variant-numeric: tabular-nums; }
+.grid { position: relative; background: #fff; border: 1px solid #ddd; }
+.row { display: flex; border-top: 1px solid #ddd; min-height: 3.2em; }
+.row .channel { flex: 0 0 10em; padding: .4em; background: #f0f0f0; overflow: hidden; }
+.line { position: relative; flex: 1; }
+.hours { border-top: none; min-height: 1.6em; background: #333; color: #fff; }
+.hours .channel { background: #333; }
+.hour { position: absolute; top: .3em; font-size: .8em; padding-left: .2em; border-left: 1px solid #888; }
+.slot { position: absolute; top: .2em; bottom: .2em; overflow: hidden; box-sizing: border-box;
+  padding: .2em .4em; background: #dbe8f6; border: 1px solid #9bb8d8; border-radius: 3px;
+  font-size: .85em; white-space: nowrap; text-overflow: ellipsis; cursor: help; }
+.slot .time { display: block; color: #555; font-size: .85em; }
+";
+
+/// Replaces the channel logos of the programmes with `data:` URIs, fetching
+/// every remote logo once through `cache`. Local logos, and ones that cannot
+/// be fetched or are not images, are left out.
+pub fn embed_icons(programs: &mut [Program], cache: Option<&Cache>) {
+    let mut embedded: HashMap<String, Option<String>> = HashMap::new();
+    for program in programs {
+        let src = match &program.channel_icon {
+            Some(icon) => icon.src.clone(),
+            None => continue,
+        };
+        let uri = embedded
+            .entry(src)
+            .or_insert_with_key(|src| data_uri(src, cache))
+            .clone();
+        match (uri, &mut program.channel_icon) {
+            (Some(uri), Some(icon)) => icon.src = uri,
+            _ => program.channel_icon = None,
+        }
+    }
+}
+
+/// Fetches a remote logo into a `data:` URI. Logos already embedded are
+/// kept, and local ones left out so that a guide cannot have local files read
+/// into the page.
+fn data_uri(src: &str, cache: Option<&Cache>) -> Option<String> {
+    if src.starts_with("data:") {
+        return Some(src.to_owned());
+    }
+    let source = match src.parse() {
+        Ok(source @ Source::Http(_)) => source,
+        _ => return None,
+    };
+    let mut bytes = Vec::new();
+    source.open(cache).ok()?.read_to_end(&mut bytes).ok()?;
+    let media_type = image_type(&bytes)?;
+    Some(format!(
+        "data:{};base64,{}",
+        media_type,
+        STANDARD.encode(&bytes)
+    ))
+}
+
+/// The media type of an image, told from its first bytes.
+fn image_type(bytes: &[u8]) -> Option<&'static str> {
+    if bytes.starts_with(b"\x89PNG") {
+        Some("image/png")
+    } else if bytes.starts_with(&[0xff, 0xd8, 0xff]) {
+        Some("image/jpeg")
+    } else if bytes.starts_with(b"GIF8") {
+        Some("image/gif")
+    } else if bytes.starts_with(b"RIFF") && bytes.get(8..12) == Some(b"WEBP") {
+        Some("image/webp")
+    } else if is_svg(bytes) {
+        Some("image/svg+xml")
+    } else {
+        None
+    }
+}
+
+/// Tells whether the root element of an XML document is `<svg>`, skipping
+/// the XML declaration, comments and doctype before it.
+fn is_svg(bytes: &[u8]) -> bool {
+    let head = String::from_utf8_lossy(&bytes[..bytes.len().min(512)]);
+    let mut rest = head.trim_start_matches('\u{feff}').trim_start();
+    while let Some(markup) = rest.strip_prefix("<?").or_else(|| rest.strip_prefix("<!")) {
+        match markup.find('>') {
+            Some(end) => rest = markup[end + 1..].trim_start(),
+            None => return false,
+        }
+    }
+    rest.strip_prefix("<svg").is_some_and(|tag| {
+        tag.starts_with(|c: char| c.is_ascii_whitespace() || c == '>' || c == '/')
+    })
+}
+
+/// Writes the programmes as a complete HTML page. Tables are split as
+/// `grouping` tells, or by day when the programmes span several days if
+/// unset; grids always come one per day.
+pub fn write_html(
+    programs: &[Program],
+    layout: HtmlLayout,
+    grouping: Option<Grouping>,
+    out: &mut impl Write,
+) -> io::Result<()> {
+    let title = match (
+        programs.iter().map(|program| program.day).min(),
+        programs.iter().map(|program| program.day).max(),
+    ) {
+        (Some(first), Some(last)) if first == last => {
+            format!("Programme TV du {}", format_date(first))
+        }
+        (Some(first), Some(last)) => format!(
+            "Programme TV du {} au {}",
+            format_date(first),
+            format_date(last)
+        ),
+        _ => "Programme TV".to_owned(),
+    };
+
+    writeln!(out, "<!DOCTYPE html>")?;
+    writeln!(out, "<html lang=\"fr\">")?;
+    writeln!(out, "<head>")?;
+    writeln!(out, "<meta charset=\"utf-8\">")?;
+    writeln!(
+        out,
+        "<meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">"
+    )?;
+    writeln!(out, "<title>{}</title>", escape(&title))?;
+    writeln!(out, "<style>\n{}</style>", STYLE)?;
+    writeln!(out, "</head>")?;
+    writeln!(out, "<body>")?;
+    writeln!(out, "<h1>{}</h1>", escape(&title))?;
+    match layout {
+        HtmlLayout::Table => write_tables(
+            programs,
+            grouping.unwrap_or_else(|| default_grouping(programs)),
+            out,
+        )?,
+        HtmlLayout::Grid => write_grids(programs, out)?,
+    }
+    writeln!(out, "</body>")?;
+    writeln!(out, "</html>")
+}
+
+fn write_tables(programs: &[Program], grouping: Grouping, out: &mut impl Write) -> io::Result<()> {
+    for (heading, programs) in split(programs, grouping) {
+        if let Some(heading) = heading {
+            writeln!(out, "<h2>{}</h2>", escape(&heading))?;
+        }
+        writeln!(out, "<table>")?;
+        writeln!(
+            out,
+            "<thead><tr><th>Chaîne</th><th>Titre</th><th>Horaires</th></tr></thead>"
+        )?;
+        writeln!(out, "<tbody>")?;
+        for program in programs {
+            write!(out, "<tr{}>", tooltip(program, false))?;
+            write!(out, "<td class=\"channel\">{}</td>", channel(program))?;
+            write!(out, "<td><strong>{}</strong>", escape(&program.title))?;
+            if let Some(sub_title) = &program.sub_title {
+                write!(out, "<span class=\"sub\">{}</span>", escape(sub_title))?;
+            }
+            writeln!(out, "</td><td class=\"time\">{}</td></tr>", times(program))?;
+        }
+        writeln!(out, "</tbody>")?;
+        writeln!(out, "</table>")?;
+    }
+    Ok(())
+}
+
+fn write_grids(programs: &[Program], out: &mut impl Write) -> io::Result<()> {
+    let days = group_by_day(programs);
+    for (date, programs) in &days {
+        let (Some(first), Some(last)) = (
+            programs.iter().map(|program| program.start).min(),
+            programs.iter().map(|program| program.end).max(),
+        ) else {
+            continue;
+        };
+        let from = floor_hour(first);
+        let to = ceil_hour(last).max(from + Duration::hours(1));
+        let span = (to - from).num_seconds() as f64;
+        let percent = |at: DateTime<FixedOffset>| {
+            ((at - from).num_seconds() as f64 / span * 100.0).clamp(0.0, 100.0)
+        };
+
+        if days.len() > 1 {
+            writeln!(out, "<h2>{}</h2>", escape(&format_date(*date)))?;
+        }
+        writeln!(out, "<div class=\"grid\">")?;
+        write!(
+            out,
+            "<div class=\"row hours\"><div class=\"channel\"></div><div class=\"line\">"
+        )?;
+        let mut hour = from;
+        while hour < to {
+            write!(
+                out,
+                "<span class=\"hour\" style=\"left: {:.3}%\">{:02}h</span>",
+                percent(hour),
+                hour.hour()
+            )?;
+            hour += Duration::hours(1);
+        }
+        writeln!(out, "</div></div>")?;
+
+        for (_, programs) in group_by_channel(programs.iter().copied()) {
+            write!(
+                out,
+                "<div class=\"row\"><div class=\"channel\">{}</div><div class=\"line\">",
+                channel(programs[0])
+            )?;
+            for program in programs {
+                let left = percent(program.start);
+                write!(
+                    out,
+                    "<div class=\"slot\" style=\"left: {:.3}%; width: {:.3}%\"{}>",
+                    left,
+                    percent(program.end) - left,
+                    tooltip(program, true)
+                )?;
+                write!(
+                    out,
+                    "<span class=\"time\">{}</span>{}</div>",
+                    times(program),
+                    escape(&program.title)
+                )?;
+            }
+            writeln!(out, "</div></div>")?;
+        }
+        writeln!(out, "</div>")?;
+    }
+    Ok(())
+}
+
+/// The channel logo, when embedded, followed by its name.
+fn channel(program: &Program) -> String {
+    let name = escape(&program.channel);
+    match &program.channel_icon {
+        Some(icon) if icon.src.starts_with("data:") => {
+            format!("<img src=\"{}\" alt=\"\">{}", escape(&icon.src), name)
+        }
+        _ => name,
+    }
+}
+
+fn times(program: &Program) -> String {
+    format!(
+        "{} – {}",
+        program.start.format("%H:%M"),
+        program.end.format("%H:%M")
+    )
+}
+
+/// A `title` attribute showing the description, and the title and times when
+/// they may be cut off on the page.
+fn tooltip(program: &Program, full: bool) -> String {
+    let mut lines: Vec<String> = Vec::new();
+    if full {
+        lines.push(format!("{} ({})", program.title, times(program)));
+        lines.extend(program.sub_title.clone());
+    }
+    lines.extend(program.description.clone());
+    match lines.is_empty() {
+        true => String::new(),
+        false => format!(" title=\"{}\"", escape(&lines.join("\n"))),
+    }
+}
+
+fn floor_hour(at: DateTime<FixedOffset>) -> DateTime<FixedOffset> {
+    at.with_minute(0)
+        .and_then(|at| at.with_second(0))
+        .and_then(|at| at.with_nanosecond(0))
+        .unwrap_or(at)
+}
+
+fn ceil_hour(at: DateTime<FixedOffset>) -> DateTime<FixedOffset> {
+    match floor_hour(at) {
+        floor if floor == at => at,
+        floor => floor + Duration::hours(1),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{programs, response, serve, GUIDE};
+
+    const PNG: &[u8] = b"\x89PNG\r\n\x1a\n";
+
+    /// The fixture guide with markup in the title of its second programme.
+    fn guide() -> String {
+        GUIDE.replace("Columbo |", "Columbo &lt;b&gt; &amp; &apos;Kojak&apos; |")
+    }
+
+    fn written(layout: HtmlLayout) -> String {
+        let mut out = Vec::new();
+        write_html(&programs(&guide()), layout, None, &mut out).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn writes_a_table_row_per_programme() {
+        let html = written(HtmlLayout::Table);
+
+        assert!(html.starts_with("<!DOCTYPE html>\n"));
+        assert!(html.contains("<title>Programme TV du mardi 20 octobre 2026</title>"));
+        // A single day needs no heading per table.
+        assert!(!html.contains("<h2>"));
+        assert!(html.contains(
+            "<tr title=\"Les &quot;aventuriers&quot; reviennent &amp; repartent.\">\
+             <td class=\"channel\">TF1</td><td><strong>Koh-Lanta, la légende</strong></td>\
+             <td class=\"time\">21:00 – 22:50</td></tr>"
+        ));
+        assert!(html.contains(
+            "<strong>Columbo &lt;b&gt; &amp; &#39;Kojak&#39; | *Meurtre* [inédit]</strong>\
+             <span class=\"sub\">Un &quot;témoin&quot;, enfin</span>"
+        ));
+        assert!(html.ends_with("</body>\n</html>\n"));
+    }
+
+    #[test]
+    fn lays_programmes_out_along_the_hours_of_the_grid() {
+        let html = written(HtmlLayout::Grid);
+
+        assert!(html.contains("<span class=\"hour\" style=\"left: 0.000%\">21h</span>"));
+        assert!(html.contains("<span class=\"hour\" style=\"left: 80.000%\">01h</span>"));
+        assert!(html.contains(
+            "<div class=\"slot\" style=\"left: 0.000%; width: 36.667%\" \
+             title=\"Koh-Lanta, la légende (21:00 – 22:50)\n\
+             Les &quot;aventuriers&quot; reviennent &amp; repartent.\">"
+        ));
+        assert!(html.contains(
+            "<div class=\"slot\" style=\"left: 50.000%; width: 40.000%\" \
+             title=\"Columbo &lt;b&gt; &amp; &#39;Kojak&#39; | *Meurtre* [inédit] (23:30 – 01:30)\n\
+             Un &quot;témoin&quot;, enfin\nPremière ligne\nseconde ligne\">"
+        ));
+        assert!(!html.contains("<table>"));
+    }
+
+    #[test]
+    fn tells_images_from_their_first_bytes() {
+        assert_eq!(image_type(PNG), Some("image/png"));
+        assert_eq!(image_type(b"\xff\xd8\xff\xe0"), Some("image/jpeg"));
+        assert_eq!(image_type(b"GIF89a"), Some("image/gif"));
+        assert_eq!(image_type(b"RIFF\0\0\0\0WEBPVP8 "), Some("image/webp"));
+        assert_eq!(
+            image_type(b"<svg xmlns=\"http://www.w3.org/2000/svg\"/>"),
+            Some("image/svg+xml")
+        );
+        assert_eq!(
+            image_type(
+                b"\xef\xbb\xbf<?xml version=\"1.0\"?>\n<!-- logo -->\n\
+                  <!DOCTYPE svg PUBLIC \"-//W3C//DTD SVG 1.1//EN\" \"svg11.dtd\">\n<svg>"
+            ),
+            Some("image/svg+xml")
+        );
+        assert_eq!(image_type(b"<html><body><svg></svg></body></html>"), None);
+        assert_eq!(image_type(b"<svgfoo/>"), None);
+        assert_eq!(image_type(b"RIFF\0\0\0\0WAVE"), None);
+        assert_eq!(image_type(b""), None);
+    }
+
+    #[test]
+    fn embeds_remote_logos_only() {
+        let (url, _requests) = serve(vec![
+            response("200 OK", &["Content-Type: image/png"], PNG),
+            response("404 Not Found", &[], b""),
+        ]);
+        let logo = format!("{}/tf1.png", url);
+        assert_eq!(
+            data_uri(&logo, None).as_deref(),
+            Some("data:image/png;base64,iVBORw0KGgo=")
+        );
+        assert_eq!(data_uri(&logo, None), None);
+
+        let embedded = "data:image/png;base64,iVBORw0KGgo=";
+        assert_eq!(data_uri(embedded, None).as_deref(), Some(embedded));
+
+        let path = std::env::temp_dir().join(format!("tvprog-logo-{}.png", std::process::id()));
+        std::fs::write(&path, PNG).unwrap();
+        let local = path.to_str().unwrap().to_owned();
+        for src in [local.clone(), format!("file://{}", local), "-".to_owned()] {
+            assert_eq!(data_uri(&src, None), None, "{} was read", src);
+        }
+        std::fs::remove_file(&path).unwrap();
+    }
+}
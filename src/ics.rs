@@ -0,0 +1,318 @@
+//! iCalendar rendering of the selected programmes, one event per programme.
+//!
+//! Events carry their times in the display time zone, described by a
+//! `VTIMEZONE` built from the tz database. Their UIDs only depend on the
+//! channel and the start time, so importing an updated guide again updates
+//! the events rather than duplicating them.
+
+use std::io::{self, Write};
+use std::time::Duration;
+
+use chrono::{DateTime, FixedOffset, NaiveDateTime, Offset, TimeZone, Utc};
+use chrono_tz::{OffsetComponents, OffsetName, Tz};
+
+use crate::cache::sanitize;
+use crate::program::Program;
+use crate::time::DEFAULT_TIMEZONE;
+
+/// Layout of local date-times, as in `DTSTART;TZID=Europe/Paris:20261020T210500`.
+const LOCAL_FORMAT: &str = "%Y%m%dT%H%M%S";
+/// Layout of UTC date-times, as in `DTSTAMP:20261020T190500Z`.
+const UTC_FORMAT: &str = "%Y%m%dT%H%M%SZ";
+/// Longest content line, in bytes, before it is folded.
+const LINE_LENGTH: usize = 75;
+
+/// Options of the calendar.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Calendar {
+    /// Time zone of the event times.
+    pub timezone: Tz,
+    /// How long before each programme a reminder goes off, if any.
+    pub alarm: Option<Duration>,
+}
+
+impl Default for Calendar {
+    fn default() -> Self {
+        Calendar {
+            timezone: DEFAULT_TIMEZONE,
+            alarm: None,
+        }
+    }
+}
+
+/// Writes the programmes as a `VCALENDAR` holding one `VEVENT` each.
+pub fn write_ics(
+    programs: &[Program],
+    calendar: &Calendar,
+    out: &mut impl Write,
+) -> io::Result<()> {
+    let mut lines = Lines(out);
+    let tzid = calendar.timezone.name();
+
+    lines.write("BEGIN:VCALENDAR")?;
+    lines.write("VERSION:2.0")?;
+    lines.write(&format!(
+        "PRODID:-//tvprog//tvprog {}//FR",
+        env!("CARGO_PKG_VERSION")
+    ))?;
+    lines.write("CALSCALE:GREGORIAN")?;
+    lines.write("METHOD:PUBLISH")?;
+    write_timezone(programs, calendar.timezone, &mut lines)?;
+
+    let stamp = Utc::now().format(UTC_FORMAT);
+    for program in programs {
+        let start = program.start.with_timezone(&calendar.timezone);
+        let end = program.end.with_timezone(&calendar.timezone);
+
+        lines.write("BEGIN:VEVENT")?;
+        lines.write(&format!("UID:{}", uid(program)))?;
+        lines.write(&format!("DTSTAMP:{}", stamp))?;
+        lines.write(&format!(
+            "DTSTART;TZID={}:{}",
+            tzid,
+            start.format(LOCAL_FORMAT)
+        ))?;
+        lines.write(&format!("DTEND;TZID={}:{}", tzid, end.format(LOCAL_FORMAT)))?;
+        lines.write(&format!("SUMMARY:{}", escape(&program.title)))?;
+        if let Some(description) = &program.description {
+            lines.write(&format!("DESCRIPTION:{}", escape(description)))?;
+        }
+        lines.write(&format!("LOCATION:{}", escape(&program.channel)))?;
+        if !program.categories.is_empty() {
+            let categories: Vec<String> = program
+                .categories
+                .iter()
+                .map(|category| escape(category))
+                .collect();
+            lines.write(&format!("CATEGORIES:{}", categories.join(",")))?;
+        }
+        if let Some(alarm) = calendar.alarm {
+            lines.write("BEGIN:VALARM")?;
+            lines.write("ACTION:DISPLAY")?;
+            lines.write(&format!("TRIGGER:-{}", ics_duration(alarm)))?;
+            lines.write(&format!("DESCRIPTION:{}", escape(&program.title)))?;
+            lines.write("END:VALARM")?;
+        }
+        lines.write("END:VEVENT")?;
+    }
+
+    lines.write("END:VCALENDAR")
+}
+
+/// Identifier of the event of `program`, stable across guide updates.
+fn uid(program: &Program) -> String {
+    format!(
+        "{}-{}@tvprog",
+        program.start.with_timezone(&Utc).format(UTC_FORMAT),
+        sanitize(&program.channel_id)
+    )
+}
+
+/// Writes the `VTIMEZONE` of `timezone`, with an observance for every offset
+/// change around the programmes.
+fn write_timezone<W: Write>(
+    programs: &[Program],
+    timezone: Tz,
+    lines: &mut Lines<W>,
+) -> io::Result<()> {
+    let first = programs.iter().map(|program| program.start).min();
+    let last = programs.iter().map(|program| program.end).max();
+    let (Some(first), Some(last)) = (first, last) else {
+        return Ok(());
+    };
+
+    let mut from = first.with_timezone(&Utc) - chrono::Duration::days(1);
+    let until = last.with_timezone(&Utc) + chrono::Duration::days(1);
+    let mut observances = vec![(from, from)];
+    while let Some(change) = next_change(timezone, from, until) {
+        observances.push((change - chrono::Duration::seconds(1), change));
+        from = change;
+    }
+
+    lines.write("BEGIN:VTIMEZONE")?;
+    lines.write(&format!("TZID:{}", timezone.name()))?;
+    for (before, at) in observances {
+        let offset_from = timezone.offset_from_utc_datetime(&before.naive_utc());
+        let offset_to = timezone.offset_from_utc_datetime(&at.naive_utc());
+        let kind = match offset_to.dst_offset().is_zero() {
+            true => "STANDARD",
+            false => "DAYLIGHT",
+        };
+        let start: NaiveDateTime =
+            at.naive_utc() + chrono::Duration::seconds(offset_from.fix().local_minus_utc().into());
+
+        lines.write(&format!("BEGIN:{}", kind))?;
+        lines.write(&format!("DTSTART:{}", start.format(LOCAL_FORMAT)))?;
+        lines.write(&format!("TZOFFSETFROM:{}", ics_offset(offset_from.fix())))?;
+        lines.write(&format!("TZOFFSETTO:{}", ics_offset(offset_to.fix())))?;
+        if let Some(name) = offset_to.abbreviation() {
+            lines.write(&format!("TZNAME:{}", name))?;
+        }
+        lines.write(&format!("END:{}", kind))?;
+    }
+    lines.write("END:VTIMEZONE")
+}
+
+/// The first instant after `from` and up to `until` at which `timezone`
+/// changes its offset, to the second.
+fn next_change(timezone: Tz, from: DateTime<Utc>, until: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    let offset = |at: DateTime<Utc>| timezone.offset_from_utc_datetime(&at.naive_utc()).fix();
+    let initial = offset(from);
+
+    let mut low = from;
+    let mut high = from;
+    while offset(high) == initial {
+        if high >= until {
+            return None;
+        }
+        low = high;
+        high += chrono::Duration::hours(1);
+    }
+    while high - low > chrono::Duration::seconds(1) {
+        let middle = low + (high - low) / 2;
+        match offset(middle) == initial {
+            true => low = middle,
+            false => high = middle,
+        }
+    }
+    Some(high)
+}
+
+/// An offset written `+0200`.
+fn ics_offset(offset: FixedOffset) -> String {
+    let seconds = offset.local_minus_utc();
+    let sign = if seconds < 0 { '-' } else { '+' };
+    let minutes = seconds.abs() / 60;
+    format!("{}{:02}{:02}", sign, minutes / 60, minutes % 60)
+}
+
+/// A duration written `PT15M`, `PT1H30M` or `P1DT2H`.
+fn ics_duration(duration: Duration) -> String {
+    let seconds = duration.as_secs();
+    let (days, seconds) = (seconds / 86_400, seconds % 86_400);
+    let mut written = String::from("P");
+    if days > 0 {
+        written.push_str(&format!("{}D", days));
+    }
+    if seconds > 0 || days == 0 {
+        written.push('T');
+        let mut rest = seconds;
+        for (unit, length) in [('H', 3_600), ('M', 60), ('S', 1)] {
+            if rest >= length {
+                written.push_str(&format!("{}{}", rest / length, unit));
+                rest %= length;
+            }
+        }
+        if seconds == 0 {
+            written.push_str("0S");
+        }
+    }
+    written
+}
+
+/// Escapes a TEXT value.
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            ';' => escaped.push_str("\\;"),
+            ',' => escaped.push_str("\\,"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Writes content lines ended by CRLF, folded at [`LINE_LENGTH`] bytes
+/// without splitting characters.
+struct Lines<W>(W);
+
+impl<W: Write> Lines<W> {
+    fn write(&mut self, line: &str) -> io::Result<()> {
+        let mut rest = line;
+        let mut limit = LINE_LENGTH;
+        while rest.len() > limit {
+            let mut split = limit;
+            while !rest.is_char_boundary(split) {
+                split -= 1;
+            }
+            write!(self.0, "{}\r\n ", &rest[..split])?;
+            rest = &rest[split..];
+            // The leading space of continuation lines counts.
+            limit = LINE_LENGTH - 1;
+        }
+        write!(self.0, "{}\r\n", rest)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{programs, GUIDE};
+
+    fn written(line: &str) -> String {
+        let mut out = Vec::new();
+        Lines(&mut out).write(line).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn leaves_short_lines_alone() {
+        assert_eq!(written("SUMMARY:Columbo"), "SUMMARY:Columbo\r\n");
+        let longest = "X".repeat(LINE_LENGTH);
+        assert_eq!(written(&longest), format!("{}\r\n", longest));
+    }
+
+    #[test]
+    fn folds_long_lines_at_75_bytes() {
+        let line = format!("DESCRIPTION:{}", "a".repeat(200));
+        let folded = written(&line);
+        let physical: Vec<&str> = folded.trim_end_matches("\r\n").split("\r\n").collect();
+
+        assert_eq!(physical.len(), 3);
+        assert!(physical.iter().all(|part| part.len() <= LINE_LENGTH));
+        assert!(physical[1..].iter().all(|part| part.starts_with(' ')));
+        assert_eq!(folded.replace("\r\n ", ""), format!("{}\r\n", line));
+    }
+
+    #[test]
+    fn never_splits_characters() {
+        let line = format!("SUMMARY:{}", "é".repeat(100));
+        let folded = written(&line);
+
+        // The output is a String: no character was cut in two.
+        assert!(folded.split("\r\n").all(|part| part.len() <= LINE_LENGTH));
+        assert_eq!(folded.replace("\r\n ", ""), format!("{}\r\n", line));
+    }
+
+    #[test]
+    fn escapes_text_values() {
+        assert_eq!(
+            escape("Meurtre; suite, et fin\\\r\nà 22h"),
+            r"Meurtre\; suite\, et fin\\\nà 22h"
+        );
+        assert_eq!(escape("Columbo"), "Columbo");
+    }
+
+    #[test]
+    fn writes_durations_and_offsets() {
+        assert_eq!(ics_duration(Duration::from_secs(15 * 60)), "PT15M");
+        assert_eq!(ics_duration(Duration::from_secs(90 * 60)), "PT1H30M");
+        assert_eq!(ics_duration(Duration::from_secs(26 * 3600)), "P1DT2H");
+        assert_eq!(ics_duration(Duration::from_secs(86_400)), "P1D");
+        assert_eq!(ics_duration(Duration::ZERO), "PT0S");
+        assert_eq!(ics_offset(FixedOffset::east_opt(7200).unwrap()), "+0200");
+        assert_eq!(ics_offset(FixedOffset::west_opt(12_600).unwrap()), "-0330");
+    }
+
+    #[test]
+    fn identifies_events_by_start_and_channel_id() {
+        let mut program = programs(GUIDE).swap_remove(0);
+        assert_eq!(uid(&program), "20261020T190000Z-tf1.fr@tvprog");
+        program.channel_id = "TF1 HD/fr".to_owned();
+        assert_eq!(uid(&program), "20261020T190000Z-TF1_HD_fr@tvprog");
+    }
+}
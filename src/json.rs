@@ -0,0 +1,316 @@
+//! JSON rendering of the selected programmes.
+//!
+//! Every programme is an object with the following members, absent values
+//! being `null`, or empty arrays and objects:
+//!
+//! | member             | type                                                     |
+//! |--------------------|----------------------------------------------------------|
+//! | `channel_id`       | XMLTV id of the channel                                  |
+//! | `channel`          | display name of the channel                              |
+//! | `start`, `end`     | RFC 3339 timestamps in the display time zone             |
+//! | `duration_minutes` | integer                                                  |
+//! | `title`            | string                                                   |
+//! | `sub_title`        | string or `null`                                         |
+//! | `description`      | string or `null`                                         |
+//! | `categories`       | category labels of the provider                          |
+//! | `genres`           | normalised genres, see [`Genre`](crate::genre::Genre)    |
+//! | `date`             | production date as given by the provider, or `null`      |
+//! | `length_minutes`   | announced length, or `null`                              |
+//! | `season`           | season from the `xmltv_ns` number, from 1, or `null`     |
+//! | `episode`          | episode from the `xmltv_ns` number, from 1, or `null`    |
+//! | `episode_nums`     | `{system, value}` objects                                |
+//! | `credits`          | `{directors, actors: [{name, role}], writers, ...}`      |
+//! | `icons`            | `{src, width, height}` objects                           |
+//! | `video`            | `{present, colour, aspect, quality}` or `null`           |
+//! | `audio`            | `{present, stereo}` or `null`                            |
+//! | `previously_shown` | `{start, channel}` or `null`                             |
+//! | `premiere`, `new`  | booleans                                                 |
+//! | `subtitles`        | `{kind, language}` objects                               |
+//! | `ratings`          | `{system, value, icons}` objects                         |
+//! | `star_ratings`     | `{system, value, icons}` objects                         |
+
+use std::io::{self, Write};
+
+use serde::Serialize;
+
+use crate::program::Program;
+use crate::xmltv::{Audio, Credits, EpisodeNum, Icon, PreviouslyShown, Rating, Subtitles, Video};
+
+/// A programme as written in JSON.
+#[derive(Serialize)]
+struct Record<'a> {
+    channel_id: &'a str,
+    channel: &'a str,
+    start: String,
+    end: String,
+    duration_minutes: i64,
+    title: &'a str,
+    sub_title: Option<&'a str>,
+    description: Option<&'a str>,
+    categories: &'a [String],
+    genres: Vec<&'static str>,
+    date: Option<&'a str>,
+    length_minutes: Option<u64>,
+    season: Option<u32>,
+    episode: Option<u32>,
+    episode_nums: &'a [EpisodeNum],
+    credits: &'a Credits,
+    icons: &'a [Icon],
+    video: Option<&'a Video>,
+    audio: Option<&'a Audio>,
+    previously_shown: Option<&'a PreviouslyShown>,
+    premiere: bool,
+    new: bool,
+    subtitles: &'a [Subtitles],
+    ratings: &'a [Rating],
+    star_ratings: &'a [Rating],
+}
+
+impl<'a> From<&'a Program> for Record<'a> {
+    fn from(program: &'a Program) -> Self {
+        let (season, episode) = program.season_episode().unwrap_or_default();
+        Record {
+            channel_id: &program.channel_id,
+            channel: &program.channel,
+            start: program.start.to_rfc3339(),
+            end: program.end.to_rfc3339(),
+            duration_minutes: (program.end - program.start).num_minutes(),
+            title: &program.title,
+            sub_title: program.sub_title.as_deref(),
+            description: program.description.as_deref(),
+            categories: &program.categories,
+            genres: program
+                .genres()
+                .into_iter()
+                .map(|genre| genre.name())
+                .collect(),
+            date: program.date.as_deref(),
+            length_minutes: program.length.map(|length| length.0.as_secs() / 60),
+            season,
+            episode,
+            episode_nums: &program.episode_nums,
+            credits: &program.credits,
+            icons: &program.icons,
+            video: program.video.as_ref(),
+            audio: program.audio.as_ref(),
+            previously_shown: program.previously_shown.as_ref(),
+            premiere: program.premiere,
+            new: program.new,
+            subtitles: &program.subtitles,
+            ratings: &program.ratings,
+            star_ratings: &program.star_ratings,
+        }
+    }
+}
+
+/// Writes the programmes as a pretty-printed JSON array.
+pub fn write_json(programs: &[Program], out: &mut impl Write) -> io::Result<()> {
+    let records: Vec<Record> = programs.iter().map(Record::from).collect();
+    serde_json::to_writer_pretty(&mut *out, &records)?;
+    writeln!(out)
+}
+
+/// Writes the programmes as newline-delimited JSON, one object per line.
+pub fn write_ndjson(programs: &[Program], out: &mut impl Write) -> io::Result<()> {
+    for program in programs {
+        serde_json::to_writer(&mut *out, &Record::from(program))?;
+        writeln!(out)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{programs, GUIDE};
+    use serde_json::{Map, Value};
+
+    /// The fixture guide with every optional element on its first programme.
+    fn guide() -> String {
+        GUIDE.replace(
+            "<new/>",
+            r#"<new/>
+    <premiere/>
+    <credits>
+      <director>Alexia Laroche-Joubert</director>
+      <actor role="Candidat">Claude Dartois</actor>
+      <presenter>Denis Brogniart</presenter>
+    </credits>
+    <date>2026</date>
+    <length units="minutes">105</length>
+    <icon src="https://example.org/koh.jpg" width="640" height="360"/>
+    <video><present>yes</present><colour>yes</colour><aspect>16:9</aspect><quality>HDTV</quality></video>
+    <audio><present>yes</present><stereo>stereo</stereo></audio>
+    <previously-shown start="20260101210000 +0100" channel="tf1.fr"/>
+    <subtitles type="teletext"><language>fr</language></subtitles>
+    <rating system="CSA"><value>-10</value><icon src="https://example.org/10.png"/></rating>
+    <star-rating><value>3/5</value></star-rating>"#,
+        )
+    }
+
+    fn records(programs: &[Program]) -> Vec<Map<String, Value>> {
+        let mut out = Vec::new();
+        write_json(programs, &mut out).unwrap();
+        match serde_json::from_slice(&out).unwrap() {
+            Value::Array(records) => records
+                .into_iter()
+                .map(|record| match record {
+                    Value::Object(record) => record,
+                    other => panic!("not an object: {}", other),
+                })
+                .collect(),
+            other => panic!("not an array: {}", other),
+        }
+    }
+
+    fn kind(value: &Value) -> &'static str {
+        match value {
+            Value::Null => "null",
+            Value::Bool(_) => "boolean",
+            Value::Number(number) if number.is_i64() || number.is_u64() => "integer",
+            Value::Number(_) => "number",
+            Value::String(_) => "string",
+            Value::Array(_) => "array",
+            Value::Object(_) => "object",
+        }
+    }
+
+    /// The members of `object` and the kinds of their values, in order.
+    fn kinds(object: &Map<String, Value>) -> Vec<(&str, &'static str)> {
+        object
+            .iter()
+            .map(|(member, value)| (member.as_str(), kind(value)))
+            .collect()
+    }
+
+    #[test]
+    fn writes_the_documented_members() {
+        let records = records(&programs(&guide()));
+        let full = [
+            ("channel_id", "string"),
+            ("channel", "string"),
+            ("start", "string"),
+            ("end", "string"),
+            ("duration_minutes", "integer"),
+            ("title", "string"),
+            ("sub_title", "null"),
+            ("description", "string"),
+            ("categories", "array"),
+            ("genres", "array"),
+            ("date", "string"),
+            ("length_minutes", "integer"),
+            ("season", "integer"),
+            ("episode", "integer"),
+            ("episode_nums", "array"),
+            ("credits", "object"),
+            ("icons", "array"),
+            ("video", "object"),
+            ("audio", "object"),
+            ("previously_shown", "object"),
+            ("premiere", "boolean"),
+            ("new", "boolean"),
+            ("subtitles", "array"),
+            ("ratings", "array"),
+            ("star_ratings", "array"),
+        ];
+        let mut sorted = full;
+        sorted.sort();
+        let mut written = kinds(&records[0]);
+        written.sort();
+        assert_eq!(written, sorted);
+
+        let record = &records[0];
+        assert_eq!(record["start"], "2026-10-20T21:00:00+02:00");
+        assert_eq!(record["duration_minutes"], 110);
+        assert_eq!(record["length_minutes"], 105);
+        assert_eq!(record["season"], 2);
+        assert_eq!(record["episode"], 5);
+        assert_eq!(
+            record["episode_nums"],
+            serde_json::json!([{"system": "xmltv_ns", "value": "1.4."}])
+        );
+        assert_eq!(
+            record["credits"]["actors"],
+            serde_json::json!([{"name": "Claude Dartois", "role": "Candidat"}])
+        );
+        assert_eq!(record["credits"]["directors"][0], "Alexia Laroche-Joubert");
+        assert_eq!(
+            record["icons"],
+            serde_json::json!([{"src": "https://example.org/koh.jpg", "width": 640, "height": 360}])
+        );
+        assert_eq!(
+            record["video"],
+            serde_json::json!({"present": true, "colour": true, "aspect": "16:9", "quality": "HDTV"})
+        );
+        assert_eq!(
+            record["audio"],
+            serde_json::json!({"present": true, "stereo": "stereo"})
+        );
+        assert_eq!(
+            record["previously_shown"],
+            serde_json::json!({"start": "20260101210000 +0100", "channel": "tf1.fr"})
+        );
+        assert_eq!(
+            record["subtitles"],
+            serde_json::json!([{"kind": "teletext", "language": "fr"}])
+        );
+        assert_eq!(
+            record["ratings"],
+            serde_json::json!([{
+                "system": "CSA",
+                "value": "-10",
+                "icons": [{"src": "https://example.org/10.png", "width": null, "height": null}],
+            }])
+        );
+        assert_eq!(
+            record["star_ratings"],
+            serde_json::json!([{"system": null, "value": "3/5", "icons": []}])
+        );
+        for genre in record["genres"].as_array().unwrap() {
+            assert_eq!(kind(genre), "string");
+        }
+    }
+
+    #[test]
+    fn writes_absent_values_as_null_or_empty() {
+        let records = records(&programs(GUIDE));
+        let record = &records[1];
+        for member in [
+            "date",
+            "length_minutes",
+            "season",
+            "episode",
+            "video",
+            "audio",
+            "previously_shown",
+        ] {
+            assert_eq!(record[member], Value::Null, "{}", member);
+        }
+        for member in [
+            "episode_nums",
+            "icons",
+            "subtitles",
+            "ratings",
+            "star_ratings",
+        ] {
+            assert_eq!(record[member], serde_json::json!([]), "{}", member);
+        }
+        assert_eq!(record["sub_title"], "Un \"témoin\", enfin");
+        assert_eq!(record["credits"]["actors"], serde_json::json!([]));
+        assert_eq!(record["premiere"], false);
+        assert_eq!(record["new"], false);
+    }
+
+    #[test]
+    fn writes_one_object_per_line_as_ndjson() {
+        let programs = programs(GUIDE);
+        let mut out = Vec::new();
+        write_ndjson(&programs, &mut out).unwrap();
+        let written = String::from_utf8(out).unwrap();
+        let lines: Vec<Map<String, Value>> = written
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines, records(&programs));
+    }
+}
@@ -0,0 +1,68 @@
+//! Choice among the language variants of a text.
+
+use std::convert::Infallible;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::xmltv::LangText;
+
+/// Languages to display, most wanted first, e.g. `fr,en`.
+///
+/// A preference matches any tag sharing its primary subtag, so `fr` picks
+/// `fr-FR` and `fr-CA` as well. When none matches, the untagged text is used,
+/// and failing that the first one of the document.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Languages(pub Vec<String>);
+
+impl Languages {
+    /// Picks the variant of a text that best suits the preference.
+    pub fn pick<'a>(&self, texts: &'a [LangText]) -> Option<&'a LangText> {
+        self.0
+            .iter()
+            .find_map(|wanted| {
+                texts.iter().find(|text| {
+                    text.lang
+                        .as_deref()
+                        .is_some_and(|lang| matches_language(lang, wanted))
+                })
+            })
+            .or_else(|| texts.iter().find(|text| text.lang.is_none()))
+            .or_else(|| texts.first())
+    }
+
+    /// Same as [`Languages::pick`], returning an owned string.
+    pub fn text(&self, texts: &[LangText]) -> Option<String> {
+        self.pick(texts).map(|text| text.value.to_owned())
+    }
+}
+
+fn matches_language(lang: &str, wanted: &str) -> bool {
+    let primary = |tag: &str| {
+        tag.split(['-', '_'])
+            .next()
+            .unwrap_or_default()
+            .to_ascii_lowercase()
+    };
+    lang.eq_ignore_ascii_case(wanted) || primary(lang) == primary(wanted)
+}
+
+impl FromStr for Languages {
+    type Err = Infallible;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        Ok(Languages(
+            value
+                .split(',')
+                .map(str::trim)
+                .filter(|lang| !lang.is_empty())
+                .map(str::to_owned)
+                .collect(),
+        ))
+    }
+}
+
+impl fmt::Display for Languages {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0.join(","))
+    }
+}
@@ -0,0 +1,50 @@
+//! Tonight's prime-time programmes on the French TNT channels, read from an
+//! XMLTV guide.
+//!
+//! The pipeline is split in four steps: [`source`] loads the guide, [`xmltv`]
+//! describes the raw document, [`filter`] selects the evening [`Program`]s and
+//! [`render`] prints them.
+
+pub mod cache;
+pub mod channels;
+pub mod compression;
+pub mod config;
+pub mod delimited;
+pub mod duration;
+pub mod error;
+pub mod filter;
+#[cfg(test)]
+mod fixtures;
+pub mod genre;
+pub mod html;
+pub mod ics;
+pub mod json;
+pub mod lang;
+pub mod markdown;
+pub mod now;
+pub mod output;
+pub mod program;
+pub mod render;
+pub mod search;
+pub mod sort;
+pub mod source;
+pub mod time;
+pub mod window;
+pub mod xmltv;
+
+pub use cache::Cache;
+pub use config::{Config, Layer, Settings};
+pub use error::{Result, TvprogError};
+pub use filter::{collect_programs, filter_programs, Filter, Selection, Strictness};
+pub use genre::{Category, Genre};
+pub use lang::Languages;
+pub use now::{on_air, OnAir};
+pub use output::{Format, Output};
+pub use program::Program;
+pub use render::{pretty_print, print_grouped, print_now, TableLayout};
+pub use search::Query;
+pub use sort::{sort_programs, Grouping, SortKey};
+pub use source::{load, Source, DEFAULT_SOURCE};
+pub use time::DateRange;
+pub use window::{Overlap, Slot, Window};
+pub use xmltv::{Item, LangText, XMLChannel, XMLProgram, XmltvReader};
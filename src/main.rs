@@ -1,134 +1,334 @@
-use chrono::{DateTime, Datelike, FixedOffset, Local, NaiveTime};
-use reqwest::blocking::get;
-use serde::Deserialize;
-
-#[derive(Debug)]
-struct Program {
-    start: DateTime<FixedOffset>,
-    end: DateTime<FixedOffset>,
-    title: String,
-    channel: String,
-}
+use std::path::PathBuf;
+use std::process::ExitCode;
 
-#[derive(Clone, Debug, Deserialize, PartialEq)]
-struct XMLChannel {
-    id: String,
-    #[serde(rename = "display-name")]
-    display_name: String,
-}
+use clap::{Parser, Subcommand};
+use tvprog::html::embed_icons;
+use tvprog::now::is_around;
+use tvprog::output::to_stdout;
+use tvprog::time::{now_in, parse_instant};
+use tvprog::{
+    collect_programs, filter_programs, load, on_air, print_now, sort_programs, Cache, Config,
+    Format, Grouping, Layer, Output, Query, Selection, Settings, SortKey,
+};
 
-#[derive(Debug, Deserialize, PartialEq)]
-struct XMLProgram {
-    start: String,
-    stop: String,
-    channel: String,
-    title: String,
-}
+/// Tonight's prime-time programmes on the French TNT channels.
+///
+/// Settings come from, by increasing precedence: the built-in defaults, the
+/// configuration file, its selected profile, the TVPROG_* environment
+/// variables and the command-line flags.
+#[derive(Parser)]
+#[command(name = "tvprog", version)]
+struct Args {
+    #[command(subcommand)]
+    command: Option<Command>,
 
-#[derive(Debug, Deserialize, PartialEq)]
-struct Xml {
-    #[serde(rename = "$unflatten=channel")]
-    channels: Vec<XMLChannel>,
-    #[serde(rename = "$unflatten=programme")]
-    programs: Vec<XMLProgram>,
-}
+    /// Configuration file (defaults to ~/.config/tvprog/config.toml).
+    #[arg(long, env = "TVPROG_CONFIG", global = true)]
+    config: Option<PathBuf>,
 
-const CHANNELS: [&str; 19] = [
-    "TF1",
-    "France 2",
-    "France 3",
-    "Canal+",
-    "France 5",
-    "M6",
-    "Arte",
-    "C8",
-    "W9",
-    "TMC",
-    "TFX",
-    "NRJ 12",
-    "France 4",
-    "CSTAR",
-    "L'Equipe",
-    "6ter",
-    "RMC Story",
-    "RMC Découverte",
-    "Chérie 25",
-];
-
-fn filter_programs(xml: &Xml) -> Vec<Program> {
-    let filtered_channel_ids: Vec<String> = filter_channel_ids(&xml.channels);
-
-    xml.programs
-        .iter()
-        .filter(|program| filtered_channel_ids.contains(&program.channel))
-        .filter(|program| is_evening_program(&program.start, &program.stop))
-        .map(|program| Program {
-            start: DateTime::parse_from_str(&program.start, "%Y%m%d%H%M%S %z").unwrap(),
-            end: DateTime::parse_from_str(&program.stop, "%Y%m%d%H%M%S %z").unwrap(),
-            title: program.title.to_owned(),
-            channel: channel_id_to_name(&program.channel, &xml.channels).to_string(),
-        })
-        .collect()
-}
+    /// Profile of the configuration file to apply.
+    #[arg(long, env = "TVPROG_PROFILE", global = true)]
+    profile: Option<String>,
+
+    /// Guide to read: an http(s) URL, a file:// URL, a local path or `-` for stdin.
+    #[arg(long, global = true)]
+    source: Option<String>,
+
+    /// Directory where remote guides are cached (defaults to the XDG cache).
+    #[arg(long, global = true)]
+    cache_dir: Option<PathBuf>,
+
+    /// Always download remote guides, bypassing the cache.
+    #[arg(long, conflicts_with = "offline", global = true)]
+    no_cache: bool,
+
+    /// How long a cached guide is used before asking the server again [default: 1h].
+    #[arg(long, global = true)]
+    max_age: Option<String>,
+
+    /// Only use the cached copy of remote guides.
+    #[arg(long, global = true)]
+    offline: bool,
+
+    /// Channel to keep, by display name; repeat to keep several.
+    #[arg(long = "channel", value_name = "NAME", global = true)]
+    channels: Vec<String>,
+
+    /// Named list of channels to keep: tnt-national or tnt-full.
+    #[arg(long, conflicts_with = "channels", global = true)]
+    preset: Option<String>,
+
+    /// Keep the programmes of this category: a genre such as film, telefilm,
+    /// serie, magazine, documentaire, information, sport or jeunesse, or any
+    /// text of the provider's labels; repeat to keep several.
+    #[arg(long = "category", value_name = "CATEGORY", global = true)]
+    categories: Vec<String>,
+
+    /// Leave out the programmes of this category; repeat to leave out several.
+    #[arg(long = "exclude-category", value_name = "CATEGORY", global = true)]
+    exclude_categories: Vec<String>,
+
+    /// Preferred languages for titles, descriptions and channel names, most
+    /// wanted first [default: fr].
+    #[arg(long, global = true)]
+    lang: Option<String>,
+
+    /// Time zone programmes are shown and filtered in [default: Europe/Paris].
+    #[arg(long, global = true)]
+    timezone: Option<String>,
+
+    /// Day to list: 2026-10-20, 20/10/2026, today, tomorrow, +2d or a weekday
+    /// name such as samedi [default: today].
+    #[arg(long, global = true)]
+    date: Option<String>,
+
+    /// Same as --date tomorrow.
+    #[arg(long, conflicts_with = "date", global = true)]
+    tomorrow: bool,
+
+    /// Number of days to list, starting with --date [default: 1].
+    #[arg(long, global = true)]
+    days: Option<u32>,
+
+    /// Named time slot: matinee, access, premiere-partie, deuxieme-partie or nuit.
+    #[arg(long, global = true)]
+    slot: Option<String>,
+
+    /// Start of the time slot, e.g. 20:30 [default: 20:45].
+    #[arg(long, value_name = "TIME", global = true)]
+    from: Option<String>,
+
+    /// End of the time slot, e.g. 21:30; an end before the start spans
+    /// midnight [default: 21:20].
+    #[arg(long, value_name = "TIME", global = true)]
+    to: Option<String>,
 
-fn filter_channel_ids(channels: &Vec<XMLChannel>) -> Vec<String> {
-    channels
-        .into_iter()
-        .filter(|channel| CHANNELS.contains(&channel.display_name.as_str()))
-        .map(|channel| channel.id.to_owned())
-        .collect()
+    /// Programmes have to last longer than this, e.g. 30m [default: 35m].
+    #[arg(long, value_name = "DURATION", global = true)]
+    min_duration: Option<String>,
+
+    /// How programmes have to meet the time slot: starts (in it), during (on
+    /// air at some point of it) or ends (in it) [default: starts].
+    #[arg(long, global = true)]
+    overlap: Option<String>,
+
+    /// Skip the programmes that cannot be read instead of reporting them and
+    /// failing once the others are listed.
+    #[arg(long, global = true)]
+    lenient: bool,
+
+    /// Order of the programmes: channel (TNT numbering), start, title or
+    /// duration [default: channel, start for search].
+    #[arg(long, global = true)]
+    sort: Option<String>,
+
+    /// Output format: table, json, ndjson, csv, tsv, ics, html, markdown or
+    /// xmltv [default: table].
+    #[arg(long, global = true)]
+    format: Option<String>,
+
+    /// Columns of the csv and tsv formats, among channel, channel_id, start,
+    /// end, duration, title, sub_title, description, category, genre and
+    /// episode [default: channel,start,end,title,category].
+    #[arg(long, value_delimiter = ',', global = true)]
+    columns: Vec<String>,
+
+    /// Remind of each programme this long before it starts, in the ics
+    /// format, e.g. 15m.
+    #[arg(long, value_name = "DURATION", global = true)]
+    alarm: Option<String>,
+
+    /// Layout of the html format: table, or grid for one row per channel
+    /// along a time line [default: table].
+    #[arg(long, global = true)]
+    html_layout: Option<String>,
+
+    /// One table per day or per channel, or none for a single table
+    /// [default: day when several days are listed].
+    #[arg(long, global = true)]
+    group: Option<String>,
 }
 
-fn is_evening_program(start_date: &str, end_date: &str) -> bool {
-    let minimum_program_start: NaiveTime = NaiveTime::from_hms(20, 45, 0);
-    let maximum_program_start: NaiveTime = NaiveTime::from_hms(21, 20, 0);
-    let now = Local::today();
-    let start_parsed = DateTime::parse_from_str(start_date, "%Y%m%d%H%M%S %z").unwrap();
-    let end_parsed = DateTime::parse_from_str(end_date, "%Y%m%d%H%M%S %z").unwrap();
-    let duration = end_parsed.signed_duration_since(start_parsed);
-
-    now.year() == start_parsed.year()
-        && now.month() == start_parsed.month()
-        && now.day() == start_parsed.day()
-        && start_parsed.time() > minimum_program_start
-        && start_parsed.time() < maximum_program_start
-        && duration.num_minutes() > 35
+#[derive(Subcommand)]
+enum Command {
+    /// Show what is on air on every channel, and what comes next.
+    ///
+    /// With a --format other than table, the programme on air and the next
+    /// one of every channel are written in that format.
+    Now {
+        /// Instant to look at, e.g. 21:30 or 2026-10-20 21:30 [default: now].
+        #[arg(long)]
+        at: Option<String>,
+    },
+    /// Search the whole guide by title, sub-title and description, ignoring
+    /// case and accents.
+    Search {
+        /// Words to look for, or a regular expression with --regex.
+        pattern: String,
+        /// Read the pattern as a regular expression.
+        #[arg(long)]
+        regex: bool,
+        /// Leave out the programmes already over.
+        #[arg(long)]
+        upcoming: bool,
+    },
+    /// Inspect the configuration.
+    Config {
+        #[command(subcommand)]
+        action: ConfigAction,
+    },
 }
 
-fn channel_id_to_name<'a>(channel_id: &str, channels: &'a Vec<XMLChannel>) -> &'a str {
-    let found_channel = channels
-        .into_iter()
-        .find(|channel| channel.id == channel_id)
-        .unwrap();
-    &found_channel.display_name
+#[derive(Subcommand)]
+enum ConfigAction {
+    /// Print the effective settings, every layer merged.
+    Show,
 }
 
-fn pretty_print(programs: &Vec<Program>) {
-    println!("┌{}┬{}┬{}┐", "─".repeat(16), "─".repeat(57), "─".repeat(15));
-    println!("│ {:14} │ {:55} │ {:13} │", "Chaine", "Titre", "Horaires");
-    println!("├{}┼{}┼{}┤", "─".repeat(16), "─".repeat(57), "─".repeat(15));
-    for program in programs {
-        println!(
-            "│ {:14} │ {:55} │ {} - {} │",
-            program.channel,
-            str_truncate(&program.title, 55),
-            program.start.format("%H:%M"),
-            program.end.format("%H:%M")
-        )
+impl Args {
+    fn layer(&self) -> Layer {
+        Layer {
+            source: self.source.clone(),
+            cache_dir: self.cache_dir.clone(),
+            no_cache: self.no_cache.then_some(true),
+            max_age: self.max_age.clone(),
+            offline: self.offline.then_some(true),
+            lenient: self.lenient.then_some(true),
+            preset: self.preset.clone(),
+            channels: (!self.channels.is_empty()).then(|| self.channels.clone()),
+            categories: (!self.categories.is_empty()).then(|| self.categories.clone()),
+            exclude_categories: (!self.exclude_categories.is_empty())
+                .then(|| self.exclude_categories.clone()),
+            lang: self.lang.clone(),
+            timezone: self.timezone.clone(),
+            date: match self.tomorrow {
+                true => Some("tomorrow".to_owned()),
+                false => self.date.clone(),
+            },
+            days: self.days,
+            slot: self.slot.clone(),
+            from: self.from.clone(),
+            to: self.to.clone(),
+            min_duration: self.min_duration.clone(),
+            overlap: self.overlap.clone(),
+            sort: self.sort.clone(),
+            group: self.group.clone(),
+            format: self.format.clone(),
+            columns: (!self.columns.is_empty()).then(|| self.columns.clone()),
+            alarm: self.alarm.clone(),
+            html_layout: self.html_layout.clone(),
+            ..Layer::default()
+        }
     }
-    println!("└{}┴{}┴{}┘", "─".repeat(16), "─".repeat(57), "─".repeat(15));
-}
 
-fn str_truncate(string: &str, limit: u32) -> String {
-    string.chars().take(limit as usize).collect()
+    fn settings(&self) -> tvprog::Result<Settings> {
+        let config = match &self.config {
+            Some(path) => Config::load(path)?,
+            None => Config::load_default()?,
+        };
+        let layer = config.merged(self.profile.as_deref(), Layer::from_env()?, self.layer())?;
+
+        Settings::resolve(layer)
+    }
 }
 
-fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let res = get("https://xmltv.ch/xmltv/xmltv-tnt.xml")?;
-    let xml: Xml = quick_xml::de::from_slice(&res.bytes()?)?;
-    let filtered_programs: Vec<Program> = filter_programs(&xml);
+fn run(args: Args) -> tvprog::Result<()> {
+    let settings = args.settings()?;
 
-    pretty_print(&filtered_programs);
+    match args.command {
+        Some(Command::Config {
+            action: ConfigAction::Show,
+        }) => print!("{}", settings.to_toml()),
+        Some(Command::Now { at }) => {
+            let timezone = settings.filter.timezone;
+            let at = match at {
+                Some(at) => parse_instant(&at, timezone)?,
+                None => now_in(timezone),
+            };
+            let cache = settings.cache();
+            let guide = load(&settings.source, cache.as_ref())?;
+            let mut selection =
+                collect_programs(guide, &settings.filter, |program| is_around(program, at))?;
+            let errors = selection.take_errors(settings.strictness());
+            let entries = on_air(&selection.programs, &settings.filter.channels, at);
+
+            match settings.output.format {
+                Format::Table => {
+                    to_stdout(|out| print_now(&entries, at, &settings.output.layout, out))?
+                }
+                _ => {
+                    selection.programs = entries
+                        .into_iter()
+                        .flat_map(|entry| entry.current.into_iter().chain(entry.next))
+                        .collect();
+                    print(&settings.output, cache.as_ref(), &mut selection)?;
+                }
+            }
+            errors?;
+        }
+        Some(Command::Search {
+            pattern,
+            regex,
+            upcoming,
+        }) => {
+            let query = match regex {
+                true => Query::regex(&pattern)?,
+                false => Query::text(&pattern),
+            };
+            let now = now_in(settings.filter.timezone);
+            let cache = settings.cache();
+            let guide = load(&settings.source, cache.as_ref())?;
+            let mut selection = collect_programs(guide, &settings.filter, |program| {
+                (!upcoming || program.end > now) && query.matches(program)
+            })?;
+            let errors = selection.take_errors(settings.strictness());
+            sort_programs(
+                &mut selection.programs,
+                settings.sort.unwrap_or(SortKey::Start),
+                &settings.filter.channels,
+            );
+
+            let output = Output {
+                group: Some(settings.output.group.unwrap_or(Grouping::Day)),
+                ..settings.output
+            };
+            print(&output, cache.as_ref(), &mut selection)?;
+            errors?;
+        }
+        None => {
+            let cache = settings.cache();
+            let guide = load(&settings.source, cache.as_ref())?;
+            let mut selection = filter_programs(guide, &settings.filter)?;
+            let errors = selection.take_errors(settings.strictness());
+            sort_programs(
+                &mut selection.programs,
+                settings.sort.unwrap_or_default(),
+                &settings.filter.channels,
+            );
+
+            print(&settings.output, cache.as_ref(), &mut selection)?;
+            errors?;
+        }
+    }
 
     Ok(())
 }
+
+/// Writes the selection as `output` tells, with the channel logos of HTML
+/// pages embedded.
+fn print(output: &Output, cache: Option<&Cache>, selection: &mut Selection) -> tvprog::Result<()> {
+    if output.format == Format::Html {
+        embed_icons(&mut selection.programs, cache);
+    }
+    output.print(selection)
+}
+
+fn main() -> ExitCode {
+    match run(Args::parse()) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("error: {}", err);
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,110 @@
+//! GitHub-flavoured Markdown rendering of the selected programmes, to post
+//! them on a chat or a wiki.
+
+use std::io::{self, Write};
+
+use crate::program::Program;
+use crate::render::split;
+use crate::sort::Grouping;
+
+/// Writes the programmes as Markdown tables, one per group under a heading.
+pub fn write_markdown(
+    programs: &[Program],
+    grouping: Grouping,
+    out: &mut impl Write,
+) -> io::Result<()> {
+    for (i, (heading, programs)) in split(programs, grouping).iter().enumerate() {
+        if i > 0 {
+            writeln!(out)?;
+        }
+        if let Some(heading) = heading {
+            writeln!(out, "### {}", escape(heading))?;
+            writeln!(out)?;
+        }
+        writeln!(out, "| Chaîne | Titre | Horaires |")?;
+        writeln!(out, "| --- | --- | --- |")?;
+        for program in programs {
+            let title = match &program.sub_title {
+                Some(sub_title) => {
+                    format!("**{}** – {}", escape(&program.title), escape(sub_title))
+                }
+                None => format!("**{}**", escape(&program.title)),
+            };
+            writeln!(
+                out,
+                "| {} | {} | {} – {} |",
+                escape(&program.channel),
+                title,
+                program.start.format("%H:%M"),
+                program.end.format("%H:%M")
+            )?;
+        }
+    }
+    Ok(())
+}
+
+/// Escapes the characters Markdown would read as table separators or
+/// formatting, and keeps the text on one line.
+pub fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\\' | '|' | '*' | '_' | '`' | '[' | ']' | '<' | '>' | '#' | '~' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            '\n' | '\r' | '\t' => escaped.push(' '),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{programs, GUIDE};
+
+    #[test]
+    fn escapes_table_separators_and_formatting() {
+        assert_eq!(
+            escape("Columbo | *Meurtre* [inédit]"),
+            "Columbo \\| \\*Meurtre\\* \\[inédit\\]"
+        );
+        assert_eq!(escape("a\\b_c`d<e>#f~"), "a\\\\b\\_c\\`d\\<e\\>\\#f\\~");
+        assert_eq!(escape("deux\nlignes\tici"), "deux lignes ici");
+    }
+
+    #[test]
+    fn keeps_one_row_per_programme_with_three_cells() {
+        let mut out = Vec::new();
+        write_markdown(&programs(GUIDE), Grouping::None, &mut out).unwrap();
+        let markdown = String::from_utf8(out).unwrap();
+        let rows: Vec<&str> = markdown.lines().collect();
+
+        assert_eq!(rows.len(), 4);
+        assert_eq!(
+            rows[3],
+            "| France 2 | **Columbo \\| \\*Meurtre\\* \\[inédit\\]** – Un \"témoin\", enfin | 23:30 – 01:30 |"
+        );
+        for row in rows {
+            let separators = row
+                .match_indices('|')
+                .filter(|(at, _)| *at == 0 || row.as_bytes()[at - 1] != b'\\');
+            assert_eq!(separators.count(), 4, "{}", row);
+        }
+    }
+
+    #[test]
+    fn heads_every_group() {
+        let mut out = Vec::new();
+        write_markdown(&programs(GUIDE), Grouping::Channel, &mut out).unwrap();
+        let markdown = String::from_utf8(out).unwrap();
+        let headings: Vec<&str> = markdown
+            .lines()
+            .filter(|line| line.starts_with("### "))
+            .collect();
+
+        assert_eq!(headings, ["### TF1", "### France 2"]);
+    }
+}
@@ -0,0 +1,195 @@
+//! What is on air at a given instant, and what comes next.
+
+use chrono::{DateTime, Duration, FixedOffset};
+
+use crate::program::Program;
+
+/// How far after the instant the next programme is looked for.
+const LOOKAHEAD_HOURS: i64 = 12;
+
+/// The programme airing on a channel and the one following it.
+#[derive(Clone, Debug)]
+pub struct OnAir {
+    pub channel: String,
+    pub current: Option<Program>,
+    pub next: Option<Program>,
+}
+
+impl OnAir {
+    /// Share of the current programme already aired at `at`, from 0 to 1.
+    pub fn progress(&self, at: DateTime<FixedOffset>) -> Option<f64> {
+        let current = self.current.as_ref()?;
+        let total = (current.end - current.start).num_seconds();
+        if total <= 0 {
+            return None;
+        }
+        let elapsed = (at - current.start).num_seconds().clamp(0, total);
+        Some(elapsed as f64 / total as f64)
+    }
+}
+
+/// Tells whether a programme may be on air at `at` or follow what is, to be
+/// given to [`collect_programs`](crate::filter::collect_programs).
+pub fn is_around(program: &Program, at: DateTime<FixedOffset>) -> bool {
+    program.end > at && program.start < at + Duration::hours(LOOKAHEAD_HOURS)
+}
+
+/// Lists, for every channel having programmes, the one on air at `at` and the
+/// next one. Channels come in the order of `channels`, the others last.
+pub fn on_air(programs: &[Program], channels: &[String], at: DateTime<FixedOffset>) -> Vec<OnAir> {
+    let mut names: Vec<&str> = Vec::new();
+    for program in programs {
+        if !names.contains(&program.channel.as_str()) {
+            names.push(&program.channel);
+        }
+    }
+    names.sort_by_key(|name| {
+        channels
+            .iter()
+            .position(|channel| channel == name)
+            .unwrap_or(channels.len())
+    });
+
+    names
+        .into_iter()
+        .map(|channel| {
+            let mut upcoming: Vec<&Program> = programs
+                .iter()
+                .filter(|program| program.channel == channel)
+                .collect();
+            upcoming.sort_by_key(|program| program.start);
+            let current = upcoming
+                .iter()
+                .find(|program| program.start <= at && at < program.end)
+                .map(|program| (*program).clone());
+            let next = upcoming
+                .iter()
+                .find(|program| program.start > at)
+                .map(|program| (*program).clone());
+            OnAir {
+                channel: channel.to_owned(),
+                current,
+                next,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{programs, GUIDE};
+
+    /// The fixture programmes with a second one on TF1, from 22:50 to 23:40.
+    fn guide() -> Vec<Program> {
+        let mut programs = programs(GUIDE);
+        let mut next = programs[0].clone();
+        next.title = "Esprits criminels".to_owned();
+        next.start = programs[0].end;
+        next.end = next.start + Duration::minutes(50);
+        programs.push(next);
+        programs
+    }
+
+    fn at(time: &str) -> DateTime<FixedOffset> {
+        DateTime::parse_from_rfc3339(&format!("2026-10-20T{}:00+02:00", time)).unwrap()
+    }
+
+    fn titles(entry: &OnAir) -> (&str, Option<&str>, Option<&str>) {
+        (
+            &entry.channel,
+            entry.current.as_ref().map(|program| program.title.as_str()),
+            entry.next.as_ref().map(|program| program.title.as_str()),
+        )
+    }
+
+    #[test]
+    fn lists_what_is_on_air_and_what_comes_next() {
+        let programs = guide();
+        let channels = ["France 2".to_owned(), "TF1".to_owned()];
+        let entries = on_air(&programs, &channels, at("22:00"));
+        assert_eq!(
+            entries.iter().map(titles).collect::<Vec<_>>(),
+            [
+                ("France 2", None, Some("Columbo | *Meurtre* [inédit]")),
+                (
+                    "TF1",
+                    Some("Koh-Lanta, la légende"),
+                    Some("Esprits criminels")
+                ),
+            ]
+        );
+
+        // Channels outside the selection come last.
+        let entries = on_air(&programs, &["France 2".to_owned()], at("22:00"));
+        assert_eq!(entries[1].channel, "TF1");
+    }
+
+    #[test]
+    fn airs_programmes_from_their_start_until_their_end() {
+        let programs = guide();
+        let tf1 = |time| on_air(&programs, &[], at(time)).swap_remove(0);
+
+        // At its start, a programme is on air and the next one is the following.
+        assert_eq!(
+            titles(&tf1("21:00")),
+            (
+                "TF1",
+                Some("Koh-Lanta, la légende"),
+                Some("Esprits criminels")
+            )
+        );
+        // At its end, the following one has taken over.
+        assert_eq!(
+            titles(&tf1("22:50")),
+            ("TF1", Some("Esprits criminels"), None)
+        );
+        assert_eq!(titles(&tf1("23:40")), ("TF1", None, None));
+        assert_eq!(
+            titles(&tf1("20:59")),
+            ("TF1", None, Some("Koh-Lanta, la légende"))
+        );
+    }
+
+    #[test]
+    fn keeps_the_programmes_airing_or_starting_within_the_lookahead() {
+        let program = &programs(GUIDE)[0];
+        assert!(is_around(program, at("21:00")));
+        assert!(is_around(program, at("22:49")));
+        assert!(!is_around(program, at("22:50")));
+        // 12 hours before its start is too early, a minute less is not.
+        let before = |minutes| program.start - Duration::minutes(minutes);
+        assert!(!is_around(program, before(12 * 60)));
+        assert!(is_around(program, before(12 * 60 - 1)));
+    }
+
+    #[test]
+    fn measures_the_share_of_the_current_programme_aired() {
+        let program = programs(GUIDE).swap_remove(0);
+        let entry = OnAir {
+            channel: program.channel.clone(),
+            current: Some(program.clone()),
+            next: None,
+        };
+        assert_eq!(entry.progress(at("21:00")), Some(0.0));
+        assert_eq!(entry.progress(at("21:55")), Some(0.5));
+        assert_eq!(entry.progress(at("22:50")), Some(1.0));
+        // Clamped outside of the programme.
+        assert_eq!(entry.progress(at("20:00")), Some(0.0));
+        assert_eq!(entry.progress(at("23:00")), Some(1.0));
+
+        let empty = OnAir {
+            current: Some(Program {
+                end: program.start,
+                ..program
+            }),
+            ..entry.clone()
+        };
+        assert_eq!(empty.progress(at("21:00")), None);
+        let off_air = OnAir {
+            current: None,
+            ..entry
+        };
+        assert_eq!(off_air.progress(at("21:00")), None);
+    }
+}
@@ -0,0 +1,163 @@
+//! Output formats of the programme listings.
+
+use std::fmt;
+use std::io::{self, StdoutLock, Write};
+use std::str::FromStr;
+
+use crate::delimited::{write_delimited, Column, DEFAULT_COLUMNS};
+use crate::error::{Result, TvprogError};
+use crate::filter::Selection;
+use crate::html::{write_html, HtmlLayout};
+use crate::ics::{write_ics, Calendar};
+use crate::json::{write_json, write_ndjson};
+use crate::markdown::write_markdown;
+use crate::render::{default_grouping, pretty_print, print_grouped, TableLayout};
+use crate::sort::Grouping;
+use crate::xmltv::write_xmltv;
+
+/// How the programmes are written out.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Format {
+    /// Box-drawn tables, see [`pretty_print`].
+    #[default]
+    Table,
+    /// A JSON array, see [`json`](crate::json) for the schema.
+    Json,
+    /// One JSON object per line.
+    Ndjson,
+    /// Comma-separated values with a header row.
+    Csv,
+    /// Tab-separated values with a header row.
+    Tsv,
+    /// An iCalendar file with one event per programme.
+    Ics,
+    /// A self-contained HTML page.
+    Html,
+    /// GitHub-flavoured Markdown tables.
+    Markdown,
+    /// An XMLTV document with the selected channels and programmes as read.
+    Xmltv,
+}
+
+const FORMATS: &[(Format, &str)] = &[
+    (Format::Table, "table"),
+    (Format::Json, "json"),
+    (Format::Ndjson, "ndjson"),
+    (Format::Csv, "csv"),
+    (Format::Tsv, "tsv"),
+    (Format::Ics, "ics"),
+    (Format::Html, "html"),
+    (Format::Markdown, "markdown"),
+    (Format::Xmltv, "xmltv"),
+];
+
+impl FromStr for Format {
+    type Err = TvprogError;
+
+    fn from_str(value: &str) -> Result<Self> {
+        let name = match value.trim().to_lowercase().as_str() {
+            "jsonl" => "ndjson".to_owned(),
+            "md" => "markdown".to_owned(),
+            "xml" => "xmltv".to_owned(),
+            name => name.to_owned(),
+        };
+        FORMATS
+            .iter()
+            .find(|(_, format)| *format == name)
+            .map(|(format, _)| *format)
+            .ok_or_else(|| TvprogError::UnknownFormat(value.to_owned()))
+    }
+}
+
+impl fmt::Display for Format {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = FORMATS
+            .iter()
+            .find(|(format, _)| format == self)
+            .map(|(_, name)| *name)
+            .unwrap_or_default();
+        f.write_str(name)
+    }
+}
+
+/// Where and how the programme listings are written.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Output {
+    pub format: Format,
+    /// Column widths of the tables.
+    pub layout: TableLayout,
+    /// Split of the tables; by day when the programmes span several days if
+    /// unset.
+    pub group: Option<Grouping>,
+    /// Columns of the CSV and TSV outputs.
+    pub columns: Vec<Column>,
+    /// Time zone and reminders of the iCalendar output.
+    pub calendar: Calendar,
+    /// Layout of the HTML output.
+    pub html_layout: HtmlLayout,
+}
+
+impl Default for Output {
+    fn default() -> Self {
+        Output {
+            format: Format::default(),
+            layout: TableLayout::default(),
+            group: None,
+            columns: DEFAULT_COLUMNS.to_vec(),
+            calendar: Calendar::default(),
+            html_layout: HtmlLayout::default(),
+        }
+    }
+}
+
+impl Output {
+    /// Writes the selected programmes on stdout. The XMLTV format also writes
+    /// the root element of the guide and the channels the programmes are on.
+    pub fn print(&self, selection: &Selection) -> Result<()> {
+        let programs = &selection.programs;
+        to_stdout(|out| match self.format {
+            Format::Table => match self.group {
+                Some(group) => print_grouped(programs, &self.layout, group, out),
+                None => pretty_print(programs, &self.layout, out),
+            },
+            Format::Json => write_json(programs, out),
+            Format::Ndjson => write_ndjson(programs, out),
+            Format::Csv => write_delimited(programs, &self.columns, b',', out),
+            Format::Tsv => write_delimited(programs, &self.columns, b'\t', out),
+            Format::Ics => write_ics(programs, &self.calendar, out),
+            Format::Html => write_html(programs, self.html_layout, self.group, out),
+            Format::Markdown => write_markdown(
+                programs,
+                self.group.unwrap_or_else(|| default_grouping(programs)),
+                out,
+            ),
+            Format::Xmltv => write_xmltv(
+                &selection.tv,
+                selection
+                    .channels
+                    .iter()
+                    .filter(|channel| {
+                        programs
+                            .iter()
+                            .any(|program| program.channel_id == channel.id)
+                    })
+                    .map(|channel| &channel.element),
+                programs.iter().map(|program| &program.element),
+                out,
+            ),
+        })
+    }
+}
+
+/// Runs `write` on the locked stdout, then flushes it.
+pub fn to_stdout(write: impl FnOnce(&mut StdoutLock) -> io::Result<()>) -> Result<()> {
+    let mut out = io::stdout().lock();
+    match write(&mut out).and_then(|()| out.flush()) {
+        // The reader went away, e.g. `head`: there is nobody left to tell.
+        Err(err) if err.kind() == io::ErrorKind::BrokenPipe => Ok(()),
+        result => result.map_err(|source| TvprogError::Io {
+            location: "standard output".to_owned(),
+            source,
+        }),
+    }
+}
@@ -0,0 +1,113 @@
+use chrono::{DateTime, FixedOffset, NaiveDate};
+use chrono_tz::Tz;
+
+use crate::error::Result;
+use crate::genre::Genre;
+use crate::lang::Languages;
+use crate::xmltv::{
+    parse_timestamp, Audio, Credits, Element, EpisodeNum, Icon, Length, PreviouslyShown, Rating,
+    Subtitles, Video, XMLChannel, XMLProgram,
+};
+
+/// A programme resolved against its channel, ready to be displayed. Its times
+/// are on the clock of the display time zone.
+#[derive(Clone, Debug)]
+pub struct Program {
+    pub start: DateTime<FixedOffset>,
+    pub end: DateTime<FixedOffset>,
+    /// Day the programme is listed under: the day whose time slot it met, or
+    /// else the day it starts on.
+    pub day: NaiveDate,
+    pub title: String,
+    /// Display name of the channel.
+    pub channel: String,
+    /// XMLTV id of the channel.
+    pub channel_id: String,
+    /// Logo of the channel.
+    pub channel_icon: Option<Icon>,
+    pub sub_title: Option<String>,
+    pub description: Option<String>,
+    pub credits: Credits,
+    pub date: Option<String>,
+    pub categories: Vec<String>,
+    pub length: Option<Length>,
+    pub icons: Vec<Icon>,
+    pub episode_nums: Vec<EpisodeNum>,
+    pub video: Option<Video>,
+    pub audio: Option<Audio>,
+    pub previously_shown: Option<PreviouslyShown>,
+    pub premiere: bool,
+    pub new: bool,
+    pub subtitles: Vec<Subtitles>,
+    pub ratings: Vec<Rating>,
+    pub star_ratings: Vec<Rating>,
+    /// The `<programme>` element it was read from.
+    pub element: Element,
+}
+
+impl Program {
+    /// Genres of the categories of the programme, without duplicates.
+    pub fn genres(&self) -> Vec<Genre> {
+        let mut genres: Vec<Genre> = Vec::new();
+        for genre in self.categories.iter().filter_map(|label| Genre::of(label)) {
+            if !genres.contains(&genre) {
+                genres.push(genre);
+            }
+        }
+        genres
+    }
+
+    /// Parses the timestamps of `program` into `timezone`, attaches it to
+    /// `channel` and keeps the texts in the preferred `languages`.
+    pub fn from_xml(
+        program: XMLProgram,
+        channel: &XMLChannel,
+        languages: &Languages,
+        timezone: Tz,
+    ) -> Result<Self> {
+        let in_timezone =
+            |instant: DateTime<FixedOffset>| instant.with_timezone(&timezone).fixed_offset();
+        let start = in_timezone(parse_timestamp(&program.start)?);
+        Ok(Program {
+            start,
+            end: in_timezone(parse_timestamp(&program.stop)?),
+            day: start.date_naive(),
+            title: languages.text(&program.titles).unwrap_or_default(),
+            channel: languages.text(&channel.display_names).unwrap_or_default(),
+            channel_id: program.channel,
+            channel_icon: channel.icons.first().cloned(),
+            sub_title: languages.text(&program.sub_titles),
+            description: languages.text(&program.descriptions),
+            credits: program.credits,
+            date: program.date,
+            categories: program.categories,
+            length: program.length,
+            icons: program.icons,
+            episode_nums: program.episode_nums,
+            video: program.video,
+            audio: program.audio,
+            previously_shown: program.previously_shown,
+            premiere: program.premiere,
+            new: program.new,
+            subtitles: program.subtitles,
+            ratings: program.ratings,
+            star_ratings: program.star_ratings,
+            element: program.element,
+        })
+    }
+
+    /// The `xmltv_ns` season and episode, counted from 1.
+    pub fn season_episode(&self) -> Option<(Option<u32>, Option<u32>)> {
+        self.episode_nums
+            .iter()
+            .find_map(EpisodeNum::season_episode)
+    }
+
+    /// The episode number as shown on screen, e.g. `S03E12`.
+    pub fn onscreen_episode(&self) -> Option<&str> {
+        self.episode_nums
+            .iter()
+            .find(|num| num.system == "onscreen")
+            .map(|num| num.value.as_str())
+    }
+}
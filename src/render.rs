@@ -0,0 +1,311 @@
+//! Terminal rendering of the selected programmes.
+
+use std::collections::BTreeMap;
+use std::io::{self, Write};
+
+use chrono::{DateTime, FixedOffset, NaiveDate};
+
+use crate::channels::channel_number;
+use crate::now::OnAir;
+use crate::program::Program;
+use crate::sort::Grouping;
+use crate::time::format_date;
+
+/// Widths, in characters, of the table columns.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TableLayout {
+    pub channel_width: u32,
+    pub title_width: u32,
+}
+
+impl Default for TableLayout {
+    fn default() -> Self {
+        TableLayout {
+            channel_width: 14,
+            title_width: 55,
+        }
+    }
+}
+
+/// Prints the programmes as box-drawn tables, one per day under its date when
+/// they span several days.
+pub fn pretty_print(
+    programs: &[Program],
+    layout: &TableLayout,
+    out: &mut impl Write,
+) -> io::Result<()> {
+    print_grouped(programs, layout, default_grouping(programs), out)
+}
+
+/// Prints the programmes as box-drawn tables, one per group under its date or
+/// channel, even when there is a single group.
+pub fn print_grouped(
+    programs: &[Program],
+    layout: &TableLayout,
+    grouping: Grouping,
+    out: &mut impl Write,
+) -> io::Result<()> {
+    for (i, (heading, programs)) in split(programs, grouping).iter().enumerate() {
+        if i > 0 {
+            writeln!(out)?;
+        }
+        if let Some(heading) = heading {
+            writeln!(out, "{}", heading)?;
+        }
+        print_table(programs.iter().copied(), layout, out)?;
+    }
+    Ok(())
+}
+
+/// By day when the programmes span several days, else a single group.
+pub fn default_grouping(programs: &[Program]) -> Grouping {
+    match group_by_day(programs).len() {
+        0 | 1 => Grouping::None,
+        _ => Grouping::Day,
+    }
+}
+
+/// Splits the programmes as `grouping` tells, each group under its date or
+/// channel. [`Grouping::None`] gives a single group without heading.
+pub fn split(programs: &[Program], grouping: Grouping) -> Vec<(Option<String>, Vec<&Program>)> {
+    match grouping {
+        Grouping::Day => group_by_day(programs)
+            .into_iter()
+            .map(|(date, programs)| (Some(format_date(date)), programs))
+            .collect(),
+        Grouping::Channel => group_by_channel(programs)
+            .into_iter()
+            .map(|(channel, programs)| (Some(channel), programs))
+            .collect(),
+        Grouping::None => vec![(None, programs.iter().collect())],
+    }
+}
+
+/// Splits the programmes by the [day](Program::day) they are listed under,
+/// keeping their order within a day.
+pub fn group_by_day(programs: &[Program]) -> BTreeMap<NaiveDate, Vec<&Program>> {
+    let mut days: BTreeMap<NaiveDate, Vec<&Program>> = BTreeMap::new();
+    for program in programs {
+        days.entry(program.day).or_default().push(program);
+    }
+    days
+}
+
+/// Splits the programmes by channel, keeping their order within a channel.
+/// Channels come in TNT number order, the others as they first appear.
+pub fn group_by_channel<'a>(
+    programs: impl IntoIterator<Item = &'a Program>,
+) -> Vec<(String, Vec<&'a Program>)> {
+    let mut channels: Vec<(String, Vec<&'a Program>)> = Vec::new();
+    for program in programs {
+        match channels
+            .iter_mut()
+            .find(|(channel, _)| *channel == program.channel)
+        {
+            Some((_, programs)) => programs.push(program),
+            None => channels.push((program.channel.clone(), vec![program])),
+        }
+    }
+    channels.sort_by_key(|(channel, _)| channel_number(channel).unwrap_or(u32::MAX));
+    channels
+}
+
+fn print_table<'a>(
+    programs: impl Iterator<Item = &'a Program>,
+    layout: &TableLayout,
+    out: &mut impl Write,
+) -> io::Result<()> {
+    let channel = layout.channel_width as usize;
+    let title = layout.title_width as usize;
+
+    writeln!(
+        out,
+        "┌{}┬{}┬{}┐",
+        "─".repeat(channel + 2),
+        "─".repeat(title + 2),
+        "─".repeat(15)
+    )?;
+    writeln!(
+        out,
+        "│ {:channel$} │ {:title$} │ {:13} │",
+        "Chaine", "Titre", "Horaires"
+    )?;
+    writeln!(
+        out,
+        "├{}┼{}┼{}┤",
+        "─".repeat(channel + 2),
+        "─".repeat(title + 2),
+        "─".repeat(15)
+    )?;
+    for program in programs {
+        writeln!(
+            out,
+            "│ {:channel$} │ {:title$} │ {} - {} │",
+            str_truncate(&program.channel, layout.channel_width),
+            str_truncate(&program.title, layout.title_width),
+            program.start.format("%H:%M"),
+            program.end.format("%H:%M")
+        )?;
+    }
+    writeln!(
+        out,
+        "└{}┴{}┴{}┘",
+        "─".repeat(channel + 2),
+        "─".repeat(title + 2),
+        "─".repeat(15)
+    )
+}
+
+/// Keeps at most `limit` characters of `string`.
+pub fn str_truncate(string: &str, limit: u32) -> String {
+    string.chars().take(limit as usize).collect()
+}
+
+/// Prints, per channel, the programme on air at `at` with a progress bar and
+/// the remaining time, then the next programme.
+pub fn print_now(
+    entries: &[OnAir],
+    at: DateTime<FixedOffset>,
+    layout: &TableLayout,
+    out: &mut impl Write,
+) -> io::Result<()> {
+    let channel = layout.channel_width as usize;
+    let title = (layout.title_width / 2).max(20);
+    let title_width = title as usize;
+    let progress = PROGRESS_WIDTH + 10;
+
+    writeln!(
+        out,
+        "┌{}┬{}┬{}┬{}┐",
+        "─".repeat(channel + 2),
+        "─".repeat(title_width + 2),
+        "─".repeat(progress + 2),
+        "─".repeat(title_width + 8)
+    )?;
+    writeln!(
+        out,
+        "│ {:channel$} │ {:title_width$} │ {:progress$} │ {:w$} │",
+        "Chaine",
+        "En cours",
+        "Reste",
+        "Ensuite",
+        w = title_width + 6
+    )?;
+    writeln!(
+        out,
+        "├{}┼{}┼{}┼{}┤",
+        "─".repeat(channel + 2),
+        "─".repeat(title_width + 2),
+        "─".repeat(progress + 2),
+        "─".repeat(title_width + 8)
+    )?;
+    for entry in entries {
+        let (current, bar) = match &entry.current {
+            Some(program) => {
+                let remaining = (program.end - at).num_minutes();
+                let bar = progress_bar(entry.progress(at).unwrap_or(0.0));
+                (
+                    str_truncate(&program.title, title),
+                    format!("{} {:>4} min", bar, remaining),
+                )
+            }
+            None => (String::new(), String::new()),
+        };
+        let next = match &entry.next {
+            Some(program) => format!(
+                "{} {}",
+                program.start.format("%H:%M"),
+                str_truncate(&program.title, title)
+            ),
+            None => String::new(),
+        };
+        writeln!(
+            out,
+            "│ {:channel$} │ {:title_width$} │ {:progress$} │ {:w$} │",
+            str_truncate(&entry.channel, layout.channel_width),
+            current,
+            bar,
+            next,
+            w = title_width + 6
+        )?;
+    }
+    writeln!(
+        out,
+        "└{}┴{}┴{}┴{}┘",
+        "─".repeat(channel + 2),
+        "─".repeat(title_width + 2),
+        "─".repeat(progress + 2),
+        "─".repeat(title_width + 8)
+    )
+}
+
+const PROGRESS_WIDTH: usize = 10;
+
+fn progress_bar(progress: f64) -> String {
+    let filled = ((progress * PROGRESS_WIDTH as f64).round() as usize).min(PROGRESS_WIDTH);
+    format!(
+        "{}{}",
+        "█".repeat(filled),
+        "░".repeat(PROGRESS_WIDTH - filled)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{programs, GUIDE};
+
+    #[test]
+    fn groups_by_channel_in_tnt_number_order() {
+        let mut programs = programs(GUIDE);
+        let mut local = programs[0].clone();
+        local.channel = "Local".to_owned();
+        let mut later = programs[1].clone();
+        later.title = "Later".to_owned();
+        // France 2, Local, TF1, France 2 again.
+        programs = vec![programs[1].clone(), local, programs[0].clone(), later];
+
+        let groups: Vec<(String, Vec<&str>)> = group_by_channel(&programs)
+            .into_iter()
+            .map(|(channel, programs)| {
+                let titles = programs.iter().map(|program| program.title.as_str());
+                (channel, titles.collect())
+            })
+            .collect();
+        assert_eq!(
+            groups,
+            [
+                ("TF1".to_owned(), vec!["Koh-Lanta, la légende"]),
+                (
+                    "France 2".to_owned(),
+                    vec!["Columbo | *Meurtre* [inédit]", "Later"]
+                ),
+                ("Local".to_owned(), vec!["Koh-Lanta, la légende"]),
+            ]
+        );
+    }
+
+    #[test]
+    fn prints_a_table_per_group_under_its_heading() {
+        let layout = TableLayout {
+            channel_width: 6,
+            title_width: 12,
+        };
+        let mut out = Vec::new();
+        print_grouped(&programs(GUIDE), &layout, Grouping::Channel, &mut out).unwrap();
+        let printed = String::from_utf8(out).unwrap();
+        let lines: Vec<&str> = printed.lines().collect();
+
+        assert_eq!(lines[0], "TF1");
+        assert_eq!(
+            lines[1],
+            format!("┌{}┬{}┬{}┐", "─".repeat(8), "─".repeat(14), "─".repeat(15))
+        );
+        assert_eq!(lines[2], "│ Chaine │ Titre        │ Horaires      │");
+        assert_eq!(lines[4], "│ TF1    │ Koh-Lanta, l │ 21:00 - 22:50 │");
+        assert_eq!(lines[6], "");
+        assert_eq!(lines[7], "France 2");
+        assert_eq!(lines[11], "│ France │ Columbo | *M │ 23:30 - 01:30 │");
+        assert_eq!(lines.len(), 13);
+    }
+}
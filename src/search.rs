@@ -0,0 +1,118 @@
+//! Search of programmes by title, sub-title and description.
+
+use regex::{Regex, RegexBuilder};
+use unicode_normalization::char::is_combining_mark;
+use unicode_normalization::UnicodeNormalization;
+
+use crate::error::{Result, TvprogError};
+use crate::program::Program;
+
+/// What programmes are searched for. Matching ignores case and accents, so
+/// `eleve` finds `Élève`.
+#[derive(Clone, Debug)]
+pub enum Query {
+    /// Texts containing these words, in this order.
+    Text(String),
+    /// Texts matching this regular expression.
+    Regex(Regex),
+}
+
+impl Query {
+    pub fn text(pattern: &str) -> Self {
+        Query::Text(fold(pattern))
+    }
+
+    /// Compiles a regular expression, see the `regex` crate for its syntax.
+    pub fn regex(pattern: &str) -> Result<Self> {
+        RegexBuilder::new(&strip_accents(pattern))
+            .case_insensitive(true)
+            .build()
+            .map(Query::Regex)
+            .map_err(TvprogError::InvalidRegex)
+    }
+
+    /// Tells whether the title, sub-title or description of `program` matches.
+    pub fn matches(&self, program: &Program) -> bool {
+        [
+            Some(&program.title),
+            program.sub_title.as_ref(),
+            program.description.as_ref(),
+        ]
+        .into_iter()
+        .flatten()
+        .any(|text| self.is_match(&fold(text)))
+    }
+
+    fn is_match(&self, folded: &str) -> bool {
+        match self {
+            Query::Text(pattern) => folded.contains(pattern.as_str()),
+            Query::Regex(regex) => regex.is_match(folded),
+        }
+    }
+}
+
+/// Lowercases `text` and strips its accents.
+pub fn fold(text: &str) -> String {
+    strip_accents(text).to_lowercase()
+}
+
+fn strip_accents(text: &str) -> String {
+    text.nfd()
+        .filter(|c| !is_combining_mark(*c))
+        .nfc()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{programs, GUIDE};
+
+    /// Titles of the fixture programmes matching `query`.
+    fn found(query: &Query) -> Vec<String> {
+        programs(&GUIDE.replace("Koh-Lanta, la légende", "L'Élève Ducobu"))
+            .into_iter()
+            .filter(|program| query.matches(program))
+            .map(|program| program.title)
+            .collect()
+    }
+
+    #[test]
+    fn folds_case_and_accents() {
+        assert_eq!(fold("L'ÉLÈVE Ça Noël"), "l'eleve ca noel");
+        assert_eq!(fold("Garçon"), "garcon");
+    }
+
+    #[test]
+    fn finds_words_whatever_their_case_and_accents() {
+        for pattern in ["eleve", "ÉLÈVE", "l'elève duc"] {
+            assert_eq!(
+                found(&Query::text(pattern)),
+                ["L'Élève Ducobu"],
+                "{}",
+                pattern
+            );
+        }
+        // Sub-titles and descriptions are searched as well.
+        let columbo = ["Columbo | *Meurtre* [inédit]"];
+        assert_eq!(found(&Query::text("TEMOIN")), columbo);
+        assert_eq!(found(&Query::text("premiere ligne")), columbo);
+        assert!(found(&Query::text("élèves")).is_empty());
+    }
+
+    #[test]
+    fn matches_expressions_once_the_accents_are_stripped() {
+        let found_by = |pattern| found(&Query::regex(pattern).unwrap());
+        assert_eq!(found_by("^l'[ée]l[eè]ve\\b"), ["L'Élève Ducobu"]);
+        assert_eq!(found_by("ÉLÈVE DUCOBU$"), ["L'Élève Ducobu"]);
+        assert_eq!(
+            found_by(r"^columbo .*\[inedit\]$"),
+            ["Columbo | *Meurtre* [inédit]"]
+        );
+        assert!(found_by("^eleve").is_empty());
+        assert!(matches!(
+            Query::regex("(inédit"),
+            Err(TvprogError::InvalidRegex(_))
+        ));
+    }
+}
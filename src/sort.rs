@@ -0,0 +1,225 @@
+//! Order and grouping of the selected programmes.
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::channels::channel_number;
+use crate::error::{Result, TvprogError};
+use crate::program::Program;
+use crate::search::fold;
+
+/// What programmes are sorted on. Ties are broken by start time, then by
+/// channel.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SortKey {
+    /// TNT channel number, then the order of the selected channels.
+    #[default]
+    Channel,
+    Start,
+    /// Title, ignoring case and accents.
+    Title,
+    /// Shortest first.
+    Duration,
+}
+
+impl FromStr for SortKey {
+    type Err = TvprogError;
+
+    fn from_str(value: &str) -> Result<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "channel" | "chaine" => Ok(SortKey::Channel),
+            "start" | "time" => Ok(SortKey::Start),
+            "title" | "titre" => Ok(SortKey::Title),
+            "duration" | "duree" => Ok(SortKey::Duration),
+            _ => Err(TvprogError::UnknownSortKey(value.to_owned())),
+        }
+    }
+}
+
+impl fmt::Display for SortKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            SortKey::Channel => "channel",
+            SortKey::Start => "start",
+            SortKey::Title => "title",
+            SortKey::Duration => "duration",
+        })
+    }
+}
+
+/// How the programmes are split into several tables.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Grouping {
+    /// One table per day, under its date.
+    Day,
+    /// One table per channel, under its name.
+    Channel,
+    /// A single table.
+    None,
+}
+
+impl FromStr for Grouping {
+    type Err = TvprogError;
+
+    fn from_str(value: &str) -> Result<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "day" | "jour" => Ok(Grouping::Day),
+            "channel" | "chaine" => Ok(Grouping::Channel),
+            "none" => Ok(Grouping::None),
+            _ => Err(TvprogError::UnknownGrouping(value.to_owned())),
+        }
+    }
+}
+
+impl fmt::Display for Grouping {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Grouping::Day => "day",
+            Grouping::Channel => "channel",
+            Grouping::None => "none",
+        })
+    }
+}
+
+/// Sorts the programmes on `key`. `channels` orders the channels the TNT
+/// does not number; the others come last, by name.
+pub fn sort_programs(programs: &mut [Program], key: SortKey, channels: &[String]) {
+    let channel_rank = |program: &Program| {
+        (
+            channel_number(&program.channel).unwrap_or(u32::MAX),
+            channels
+                .iter()
+                .position(|channel| channel == &program.channel)
+                .unwrap_or(channels.len()),
+            program.channel.clone(),
+        )
+    };
+
+    match key {
+        SortKey::Channel => {
+            programs.sort_by_cached_key(|program| (channel_rank(program), program.start))
+        }
+        SortKey::Start => {
+            programs.sort_by_cached_key(|program| (program.start, channel_rank(program)))
+        }
+        SortKey::Title => programs.sort_by_cached_key(|program| {
+            (fold(&program.title), program.start, channel_rank(program))
+        }),
+        SortKey::Duration => programs.sort_by_cached_key(|program| {
+            (
+                program.end - program.start,
+                program.start,
+                channel_rank(program),
+            )
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{programs, GUIDE};
+    use chrono::Duration;
+
+    /// The first fixture programme moved to `channel`, starting `at` minutes
+    /// after it and lasting `minutes`.
+    fn program(channel: &str, title: &str, at: i64, minutes: i64) -> Program {
+        let mut program = programs(GUIDE).swap_remove(0);
+        program.channel = channel.to_owned();
+        program.title = title.to_owned();
+        program.start += Duration::minutes(at);
+        program.end = program.start + Duration::minutes(minutes);
+        program
+    }
+
+    fn sorted(mut programs: Vec<Program>, key: SortKey, channels: &[&str]) -> Vec<String> {
+        let channels: Vec<String> = channels.iter().map(|&channel| channel.to_owned()).collect();
+        sort_programs(&mut programs, key, &channels);
+        programs
+            .iter()
+            .map(|program| format!("{} {}", program.channel, program.title))
+            .collect()
+    }
+
+    #[test]
+    fn parses_keys_and_groupings_in_english_or_french() {
+        assert_eq!(" Titre ".parse::<SortKey>().unwrap(), SortKey::Title);
+        assert_eq!("DUREE".parse::<SortKey>().unwrap(), SortKey::Duration);
+        assert!(matches!(
+            "length".parse::<SortKey>(),
+            Err(TvprogError::UnknownSortKey(key)) if key == "length"
+        ));
+        assert_eq!("jour".parse::<Grouping>().unwrap(), Grouping::Day);
+        assert!("week".parse::<Grouping>().is_err());
+    }
+
+    #[test]
+    fn orders_channels_by_tnt_number_then_by_selection_then_by_name() {
+        let programs = vec![
+            program("Zeta", "z", 0, 60),
+            program("Gulli", "g", 0, 60),
+            program("Local A", "a", 0, 60),
+            program("Alpha", "al", 0, 60),
+            program("France2", "f2 late", 60, 60),
+            program("Local B", "b", 0, 60),
+            program("France 2", "f2", 0, 60),
+            program("TF1", "t", 30, 60),
+        ];
+        assert_eq!(
+            sorted(programs, SortKey::Channel, &["Local B", "Local A"]),
+            [
+                "TF1 t",
+                "France 2 f2",
+                "France2 f2 late",
+                "Gulli g",
+                "Local B b",
+                "Local A a",
+                "Alpha al",
+                "Zeta z",
+            ]
+        );
+    }
+
+    #[test]
+    fn breaks_ties_by_start_then_by_channel() {
+        let programs = || {
+            vec![
+                program("M6", "Zorro", 0, 90),
+                program("Local", "Élan", 0, 60),
+                program("Arte", "elan", 30, 60),
+                program("TF1", "Zorro", 0, 90),
+                program("France 3", "alpha", 0, 60),
+            ]
+        };
+        assert_eq!(
+            sorted(programs(), SortKey::Start, &["Local"]),
+            [
+                "TF1 Zorro",
+                "France 3 alpha",
+                "M6 Zorro",
+                "Local Élan",
+                "Arte elan"
+            ]
+        );
+        assert_eq!(
+            sorted(programs(), SortKey::Title, &["Local"]),
+            [
+                "France 3 alpha",
+                "Local Élan",
+                "Arte elan",
+                "TF1 Zorro",
+                "M6 Zorro"
+            ]
+        );
+        assert_eq!(
+            sorted(programs(), SortKey::Duration, &["Local"]),
+            [
+                "France 3 alpha",
+                "Local Élan",
+                "Arte elan",
+                "TF1 Zorro",
+                "M6 Zorro"
+            ]
+        );
+    }
+}
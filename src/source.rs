@@ -0,0 +1,159 @@
+//! Retrieval of the XMLTV guide.
+
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader};
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use reqwest::blocking::get;
+
+use crate::cache::Cache;
+use crate::compression::decompress;
+use crate::error::{Result, TvprogError};
+use crate::xmltv::XmltvReader;
+
+/// The guide used when no other source is given.
+pub const DEFAULT_SOURCE: &str = "https://xmltv.ch/xmltv/xmltv-tnt.xml";
+
+/// Where the XMLTV guide is read from.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Source {
+    /// An `http://` or `https://` URL.
+    Http(String),
+    /// A local file, given as a path or a `file://` URL.
+    File(PathBuf),
+    /// The standard input, given as `-`.
+    Stdin,
+}
+
+impl Source {
+    /// Opens the guide, going through `cache` for remote sources.
+    pub fn open(&self, cache: Option<&Cache>) -> Result<Box<dyn BufRead>> {
+        match self {
+            Source::Http(url) => match cache {
+                Some(cache) => {
+                    let path = cache.fetch(url)?;
+                    let file = File::open(&path).map_err(|err| self.io_error(err))?;
+                    Ok(Box::new(BufReader::new(file)))
+                }
+                None => {
+                    let res = get(url)?.error_for_status()?;
+                    Ok(Box::new(BufReader::new(res)))
+                }
+            },
+            Source::File(path) => {
+                let file = File::open(path).map_err(|err| self.io_error(err))?;
+                Ok(Box::new(BufReader::new(file)))
+            }
+            Source::Stdin => Ok(Box::new(BufReader::new(io::stdin()))),
+        }
+    }
+
+    fn io_error(&self, source: io::Error) -> TvprogError {
+        TvprogError::Io {
+            location: self.to_string(),
+            source,
+        }
+    }
+}
+
+impl Default for Source {
+    fn default() -> Self {
+        Source::Http(DEFAULT_SOURCE.to_owned())
+    }
+}
+
+impl FromStr for Source {
+    type Err = TvprogError;
+
+    fn from_str(value: &str) -> Result<Self> {
+        if value == "-" {
+            return Ok(Source::Stdin);
+        }
+        if value.starts_with("http://") || value.starts_with("https://") {
+            return Ok(Source::Http(value.to_owned()));
+        }
+        if let Some(path) = value.strip_prefix("file://") {
+            let path = path.strip_prefix("localhost").unwrap_or(path);
+            if !path.starts_with('/') {
+                return Err(TvprogError::UnsupportedSource(value.to_owned()));
+            }
+            return Ok(Source::File(PathBuf::from(path)));
+        }
+        if value.contains("://") {
+            return Err(TvprogError::UnsupportedSource(value.to_owned()));
+        }
+        Ok(Source::File(PathBuf::from(value)))
+    }
+}
+
+impl fmt::Display for Source {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Source::Http(url) => write!(f, "{}", url),
+            Source::File(path) => write!(f, "{}", path.display()),
+            Source::Stdin => write!(f, "-"),
+        }
+    }
+}
+
+/// Opens the XMLTV guide found at `source`, decompressing it if needed, and
+/// returns a reader streaming its channels and programmes.
+pub fn load(source: &Source, cache: Option<&Cache>) -> Result<XmltvReader<Box<dyn BufRead>>> {
+    let input = decompress(source.open(cache)?, &source.to_string())?;
+    Ok(XmltvReader::new(input))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_every_kind_of_source() {
+        assert_eq!("-".parse::<Source>().unwrap(), Source::Stdin);
+        assert_eq!(
+            "https://example.org/tnt.xml.gz".parse::<Source>().unwrap(),
+            Source::Http("https://example.org/tnt.xml.gz".to_owned())
+        );
+        assert_eq!(
+            "http://example.org/tnt.xml".parse::<Source>().unwrap(),
+            Source::Http("http://example.org/tnt.xml".to_owned())
+        );
+        assert_eq!(
+            "guides/tnt.xml".parse::<Source>().unwrap(),
+            Source::File(PathBuf::from("guides/tnt.xml"))
+        );
+        assert_eq!(
+            "file:///tmp/tnt.xml".parse::<Source>().unwrap(),
+            Source::File(PathBuf::from("/tmp/tnt.xml"))
+        );
+        assert_eq!(
+            "file://localhost/tmp/tnt.xml".parse::<Source>().unwrap(),
+            Source::File(PathBuf::from("/tmp/tnt.xml"))
+        );
+    }
+
+    #[test]
+    fn rejects_other_schemes_and_relative_file_urls() {
+        for value in ["ftp://example.org/tnt.xml", "file://tnt.xml"] {
+            assert!(matches!(
+                value.parse::<Source>(),
+                Err(TvprogError::UnsupportedSource(source)) if source == value
+            ));
+        }
+    }
+
+    #[test]
+    fn reads_a_local_file() {
+        let path = std::env::temp_dir().join(format!("tvprog-source-{}.xml", std::process::id()));
+        std::fs::write(&path, crate::fixtures::GUIDE).unwrap();
+        let items: Vec<_> = load(&Source::File(path.clone()), None)
+            .unwrap()
+            .collect::<Result<_>>()
+            .unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(items.len(), 5);
+    }
+}
@@ -0,0 +1,299 @@
+//! Times of day and dates as typed by users, and the time zone they are read in.
+
+use chrono::{
+    DateTime, Datelike, Days, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc,
+    Weekday,
+};
+use chrono_tz::Tz;
+
+use crate::error::{Result, TvprogError};
+
+/// Parses a time of day written `20:45`, `20h45`, `20h` or `20`.
+pub fn parse_time(value: &str) -> Result<NaiveTime> {
+    let invalid = || TvprogError::InvalidTime(value.to_owned());
+    let trimmed = value.trim().to_ascii_lowercase();
+    let (hours, minutes) = match trimmed.split_once([':', 'h']) {
+        Some((hours, "")) => (hours, "0"),
+        Some((hours, minutes)) => (hours, minutes),
+        None => (trimmed.as_str(), "0"),
+    };
+    let hours: u32 = hours.parse().map_err(|_| invalid())?;
+    let minutes: u32 = minutes.parse().map_err(|_| invalid())?;
+
+    NaiveTime::from_hms_opt(hours, minutes, 0).ok_or_else(invalid)
+}
+
+/// Time zone programmes are displayed and filtered in when none is configured.
+pub const DEFAULT_TIMEZONE: Tz = chrono_tz::Europe::Paris;
+
+/// Parses a time zone of the tz database, such as `Europe/Paris` or `UTC`.
+pub fn parse_timezone(value: &str) -> Result<Tz> {
+    value
+        .trim()
+        .parse()
+        .map_err(|_| TvprogError::UnknownTimezone(value.to_owned()))
+}
+
+/// The current instant, on the clock of `timezone`.
+pub fn now_in(timezone: Tz) -> DateTime<FixedOffset> {
+    Utc::now().with_timezone(&timezone).fixed_offset()
+}
+
+/// Today's date in `timezone`.
+pub fn today_in(timezone: Tz) -> NaiveDate {
+    now_in(timezone).date_naive()
+}
+
+/// The instant `naive` shows on the clock of `timezone`. Times skipped by a
+/// daylight saving change are moved an hour later, repeated ones are taken
+/// the first time.
+pub fn localize(naive: NaiveDateTime, timezone: Tz) -> DateTime<FixedOffset> {
+    timezone
+        .from_local_datetime(&naive)
+        .earliest()
+        .or_else(|| {
+            timezone
+                .from_local_datetime(&(naive + chrono::Duration::hours(1)))
+                .earliest()
+        })
+        .unwrap_or_else(|| timezone.from_utc_datetime(&naive))
+        .fixed_offset()
+}
+
+/// Parses an instant: an RFC 3339 timestamp, `2026-10-20 21:00`, or a time
+/// of day such as `21:00`, taken today. Dates and times without an offset are
+/// read on the clock of `timezone`, and the instant is returned on it.
+pub fn parse_instant(value: &str, timezone: Tz) -> Result<DateTime<FixedOffset>> {
+    if let Ok(instant) = DateTime::parse_from_rfc3339(value.trim()) {
+        return Ok(instant.with_timezone(&timezone).fixed_offset());
+    }
+    let naive = match NaiveDateTime::parse_from_str(value.trim(), "%Y-%m-%d %H:%M") {
+        Ok(naive) => naive,
+        Err(_) => today_in(timezone).and_time(parse_time(value)?),
+    };
+    timezone
+        .from_local_datetime(&naive)
+        .earliest()
+        .map(|instant| instant.fixed_offset())
+        .ok_or_else(|| TvprogError::InvalidTime(value.to_owned()))
+}
+
+/// Consecutive days, starting with `first`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DateRange {
+    pub first: NaiveDate,
+    pub days: u32,
+}
+
+impl DateRange {
+    /// The single day `date`.
+    pub fn day(date: NaiveDate) -> Self {
+        DateRange {
+            first: date,
+            days: 1,
+        }
+    }
+
+    pub fn contains(&self, date: NaiveDate) -> bool {
+        date >= self.first && (date - self.first).num_days() < i64::from(self.days)
+    }
+
+    /// Iterates over every day of the range.
+    pub fn iter(&self) -> impl Iterator<Item = NaiveDate> {
+        self.first.iter_days().take(self.days as usize)
+    }
+}
+
+impl Default for DateRange {
+    /// Today, in the default time zone.
+    fn default() -> Self {
+        DateRange::day(today_in(DEFAULT_TIMEZONE))
+    }
+}
+
+const WEEKDAYS: [(Weekday, &str, &str); 7] = [
+    (Weekday::Mon, "lundi", "monday"),
+    (Weekday::Tue, "mardi", "tuesday"),
+    (Weekday::Wed, "mercredi", "wednesday"),
+    (Weekday::Thu, "jeudi", "thursday"),
+    (Weekday::Fri, "vendredi", "friday"),
+    (Weekday::Sat, "samedi", "saturday"),
+    (Weekday::Sun, "dimanche", "sunday"),
+];
+
+const MONTHS: [&str; 12] = [
+    "janvier",
+    "février",
+    "mars",
+    "avril",
+    "mai",
+    "juin",
+    "juillet",
+    "août",
+    "septembre",
+    "octobre",
+    "novembre",
+    "décembre",
+];
+
+/// Parses a date relative to `today`: `2026-10-20`, `20/10/2026`, `today`,
+/// `tomorrow` (or `aujourd'hui`, `demain`), an offset in days such as `+2d`,
+/// or a weekday name such as `samedi`, meaning its next occurrence.
+pub fn parse_date(value: &str, today: NaiveDate) -> Result<NaiveDate> {
+    let invalid = || TvprogError::InvalidDate(value.to_owned());
+    let trimmed = value.trim().to_lowercase();
+
+    match trimmed.as_str() {
+        "today" | "aujourd'hui" | "auj" => return Ok(today),
+        "tomorrow" | "demain" => return today.checked_add_days(Days::new(1)).ok_or_else(invalid),
+        "yesterday" | "hier" => return today.checked_sub_days(Days::new(1)).ok_or_else(invalid),
+        _ => {}
+    }
+
+    if let Some(offset) = trimmed.strip_prefix(['+', '-']) {
+        let days: u64 = offset
+            .trim_end_matches(['d', 'j'])
+            .parse()
+            .map_err(|_| invalid())?;
+        let date = match trimmed.starts_with('+') {
+            true => today.checked_add_days(Days::new(days)),
+            false => today.checked_sub_days(Days::new(days)),
+        };
+        return date.ok_or_else(invalid);
+    }
+
+    if let Some((weekday, _, _)) = WEEKDAYS
+        .iter()
+        .find(|(_, french, english)| trimmed == *french || trimmed == *english)
+    {
+        let ahead =
+            (7 + weekday.num_days_from_monday() - today.weekday().num_days_from_monday()) % 7;
+        return today
+            .checked_add_days(Days::new(ahead.into()))
+            .ok_or_else(invalid);
+    }
+
+    NaiveDate::parse_from_str(&trimmed, "%Y-%m-%d")
+        .or_else(|_| NaiveDate::parse_from_str(&trimmed, "%d/%m/%Y"))
+        .map_err(|_| invalid())
+}
+
+/// Writes a date the French way, e.g. `samedi 24 octobre 2026`.
+pub fn format_date(date: NaiveDate) -> String {
+    let (_, weekday, _) = WEEKDAYS[date.weekday().num_days_from_monday() as usize];
+    let month = MONTHS[date.month0() as usize];
+    format!("{} {} {} {}", weekday, date.day(), month, date.year())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    fn time(hours: u32, minutes: u32) -> NaiveTime {
+        NaiveTime::from_hms_opt(hours, minutes, 0).unwrap()
+    }
+
+    #[test]
+    fn parses_times_of_day() {
+        assert_eq!(parse_time("20:45").unwrap(), time(20, 45));
+        assert_eq!(parse_time("20h45").unwrap(), time(20, 45));
+        assert_eq!(parse_time(" 7H05 ").unwrap(), time(7, 5));
+        assert_eq!(parse_time("20h").unwrap(), time(20, 0));
+        assert_eq!(parse_time("20").unwrap(), time(20, 0));
+        assert_eq!(parse_time("0:00").unwrap(), time(0, 0));
+    }
+
+    #[test]
+    fn rejects_invalid_times() {
+        for value in ["", "24:00", "20:60", "20:4a", "soir", "20:45:00"] {
+            assert!(
+                matches!(parse_time(value), Err(TvprogError::InvalidTime(_))),
+                "{:?} was accepted",
+                value
+            );
+        }
+    }
+
+    #[test]
+    fn parses_absolute_dates() {
+        let today = date(2026, 10, 20);
+        assert_eq!(parse_date("2026-10-24", today).unwrap(), date(2026, 10, 24));
+        assert_eq!(parse_date("24/10/2026", today).unwrap(), date(2026, 10, 24));
+    }
+
+    #[test]
+    fn parses_dates_relative_to_today() {
+        // A Tuesday.
+        let today = date(2026, 10, 20);
+        assert_eq!(parse_date("today", today).unwrap(), today);
+        assert_eq!(parse_date("Aujourd'hui", today).unwrap(), today);
+        assert_eq!(parse_date("demain", today).unwrap(), date(2026, 10, 21));
+        assert_eq!(parse_date("hier", today).unwrap(), date(2026, 10, 19));
+        assert_eq!(parse_date("+2d", today).unwrap(), date(2026, 10, 22));
+        assert_eq!(parse_date("+12j", today).unwrap(), date(2026, 11, 1));
+        assert_eq!(parse_date("-1", today).unwrap(), date(2026, 10, 19));
+        assert_eq!(parse_date("samedi", today).unwrap(), date(2026, 10, 24));
+        assert_eq!(parse_date("Monday", today).unwrap(), date(2026, 10, 26));
+        assert_eq!(parse_date("mardi", today).unwrap(), today);
+    }
+
+    #[test]
+    fn rejects_invalid_dates() {
+        let today = date(2026, 10, 20);
+        for value in ["", "2026-02-30", "31/13/2026", "+2x", "bientôt"] {
+            assert!(
+                matches!(parse_date(value, today), Err(TvprogError::InvalidDate(_))),
+                "{:?} was accepted",
+                value
+            );
+        }
+    }
+
+    #[test]
+    fn ranges_over_consecutive_days() {
+        let range = DateRange {
+            first: date(2026, 10, 30),
+            days: 3,
+        };
+        assert_eq!(
+            range.iter().collect::<Vec<_>>(),
+            [date(2026, 10, 30), date(2026, 10, 31), date(2026, 11, 1)]
+        );
+        assert!(range.contains(date(2026, 11, 1)));
+        assert!(!range.contains(date(2026, 10, 29)));
+        assert!(!range.contains(date(2026, 11, 2)));
+    }
+
+    #[test]
+    fn reads_instants_on_the_clock_of_the_time_zone() {
+        let paris = parse_timezone("Europe/Paris").unwrap();
+        let instant = |value| parse_instant(value, paris).unwrap().to_rfc3339();
+        assert_eq!(instant("2026-10-20 21:00"), "2026-10-20T21:00:00+02:00");
+        assert_eq!(instant("2026-10-20T19:00:00Z"), "2026-10-20T21:00:00+02:00");
+        assert!(matches!(
+            parse_timezone("Europe/Lutece"),
+            Err(TvprogError::UnknownTimezone(_))
+        ));
+    }
+
+    #[test]
+    fn localizes_across_daylight_saving_changes() {
+        let paris = DEFAULT_TIMEZONE;
+        // 02:30 does not exist on the last Sunday of March.
+        let skipped = localize(date(2026, 3, 29).and_time(time(2, 30)), paris);
+        assert_eq!(skipped.to_rfc3339(), "2026-03-29T03:30:00+02:00");
+        // 02:30 happens twice on the last Sunday of October.
+        let repeated = localize(date(2026, 10, 25).and_time(time(2, 30)), paris);
+        assert_eq!(repeated.to_rfc3339(), "2026-10-25T02:30:00+02:00");
+    }
+
+    #[test]
+    fn writes_dates_in_french() {
+        assert_eq!(format_date(date(2026, 10, 24)), "samedi 24 octobre 2026");
+        assert_eq!(format_date(date(2026, 8, 1)), "samedi 1 août 2026");
+    }
+}
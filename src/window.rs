@@ -0,0 +1,274 @@
+//! Time slots programmes are selected in.
+
+use std::fmt;
+use std::str::FromStr;
+use std::time::Duration;
+
+use chrono::{DateTime, Days, FixedOffset, NaiveDate, NaiveTime};
+use chrono_tz::Tz;
+
+use crate::error::{Result, TvprogError};
+use crate::time::localize;
+
+/// A time slot: programmes meeting the slot between `from` and `to`, as told
+/// by an [`Overlap`], and lasting more than `min_duration`. A slot whose `to`
+/// does not come after its `from`, such as 23:30 to 05:00, spans midnight and
+/// belongs to the day it starts on.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Window {
+    pub from: NaiveTime,
+    pub to: NaiveTime,
+    pub min_duration: Duration,
+}
+
+impl Default for Window {
+    /// The start of prime time: 20:45 to 21:20, more than 35 minutes long.
+    fn default() -> Self {
+        Window::new((20, 45), (21, 20), 35)
+    }
+}
+
+impl Window {
+    const fn new(from: (u32, u32), to: (u32, u32), min_minutes: u64) -> Self {
+        Window {
+            from: time(from.0, from.1),
+            to: time(to.0, to.1),
+            min_duration: Duration::from_secs(min_minutes * 60),
+        }
+    }
+
+    /// The instants the slot of `date` begins and ends at, on the clock of
+    /// `timezone`.
+    pub fn span(
+        &self,
+        date: NaiveDate,
+        timezone: Tz,
+    ) -> (DateTime<FixedOffset>, DateTime<FixedOffset>) {
+        let end_date = match self.to > self.from {
+            true => date,
+            false => date.checked_add_days(Days::new(1)).unwrap_or(date),
+        };
+        (
+            localize(date.and_time(self.from), timezone),
+            localize(end_date.and_time(self.to), timezone),
+        )
+    }
+}
+
+/// How a programme has to meet a slot to be selected.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Overlap {
+    /// It starts within the slot, at its beginning included.
+    #[default]
+    Starts,
+    /// It is on air at some point of the slot, whenever it started.
+    During,
+    /// It ends within the slot, at its end included.
+    Ends,
+}
+
+impl Overlap {
+    /// Tells whether a programme airing from `start` to `end` meets the slot
+    /// spanning `from` to `to`.
+    pub fn matches(
+        self,
+        (start, end): (DateTime<FixedOffset>, DateTime<FixedOffset>),
+        (from, to): (DateTime<FixedOffset>, DateTime<FixedOffset>),
+    ) -> bool {
+        match self {
+            Overlap::Starts => start >= from && start < to,
+            Overlap::During => start < to && end > from,
+            Overlap::Ends => end > from && end <= to,
+        }
+    }
+}
+
+impl FromStr for Overlap {
+    type Err = TvprogError;
+
+    fn from_str(value: &str) -> Result<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "starts" | "starts-in" | "start" => Ok(Overlap::Starts),
+            "during" | "airs-during" => Ok(Overlap::During),
+            "ends" | "ends-in" | "end" => Ok(Overlap::Ends),
+            _ => Err(TvprogError::UnknownOverlap(value.to_owned())),
+        }
+    }
+}
+
+impl fmt::Display for Overlap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Overlap::Starts => "starts",
+            Overlap::During => "during",
+            Overlap::Ends => "ends",
+        })
+    }
+}
+
+const fn time(hours: u32, minutes: u32) -> NaiveTime {
+    match NaiveTime::from_hms_opt(hours, minutes, 0) {
+        Some(time) => time,
+        None => panic!("invalid slot time"),
+    }
+}
+
+/// A named slot of the French TV day.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Slot {
+    pub name: &'static str,
+    pub aliases: &'static [&'static str],
+    pub window: Window,
+}
+
+/// Every named slot, in the order of the day.
+pub const SLOTS: &[Slot] = &[
+    Slot {
+        name: "matinee",
+        aliases: &["matinée", "morning"],
+        window: Window::new((6, 0), (12, 0), 10),
+    },
+    Slot {
+        name: "access",
+        aliases: &["access prime time"],
+        window: Window::new((17, 55), (20, 0), 10),
+    },
+    Slot {
+        name: "premiere-partie",
+        aliases: &[
+            "première partie de soirée",
+            "premiere partie de soiree",
+            "prime",
+        ],
+        window: Window::new((20, 45), (21, 20), 35),
+    },
+    Slot {
+        name: "deuxieme-partie",
+        aliases: &[
+            "deuxième partie de soirée",
+            "deuxieme partie de soiree",
+            "seconde-partie",
+        ],
+        window: Window::new((22, 10), (23, 15), 20),
+    },
+    Slot {
+        name: "nuit",
+        aliases: &["night"],
+        window: Window::new((23, 30), (5, 0), 10),
+    },
+];
+
+/// Returns the slot called `name` or one of its aliases, case-insensitively.
+pub fn slot(name: &str) -> Result<&'static Slot> {
+    let name = name.trim().to_lowercase();
+    SLOTS
+        .iter()
+        .find(|slot| slot.name == name || slot.aliases.contains(&name.as_str()))
+        .ok_or_else(|| TvprogError::UnknownSlot(name.to_owned()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::time::DEFAULT_TIMEZONE;
+
+    fn at(value: &str) -> DateTime<FixedOffset> {
+        DateTime::parse_from_rfc3339(value).unwrap()
+    }
+
+    /// The `nuit` slot of 2026-10-20, from 23:30 to 05:00 the next day.
+    fn night() -> (DateTime<FixedOffset>, DateTime<FixedOffset>) {
+        let date = NaiveDate::from_ymd_opt(2026, 10, 20).unwrap();
+        slot("nuit").unwrap().window.span(date, DEFAULT_TIMEZONE)
+    }
+
+    #[test]
+    fn spans_midnight_when_the_slot_ends_before_it_starts() {
+        assert_eq!(
+            night(),
+            (
+                at("2026-10-20T23:30:00+02:00"),
+                at("2026-10-21T05:00:00+02:00")
+            )
+        );
+    }
+
+    #[test]
+    fn matches_starts_across_midnight() {
+        let starts = |start, end| Overlap::Starts.matches((at(start), at(end)), night());
+        assert!(starts(
+            "2026-10-20T23:30:00+02:00",
+            "2026-10-21T01:30:00+02:00"
+        ));
+        assert!(starts(
+            "2026-10-21T01:30:00+02:00",
+            "2026-10-21T03:00:00+02:00"
+        ));
+        assert!(!starts(
+            "2026-10-20T23:00:00+02:00",
+            "2026-10-21T02:00:00+02:00"
+        ));
+        assert!(!starts(
+            "2026-10-21T05:00:00+02:00",
+            "2026-10-21T06:00:00+02:00"
+        ));
+        assert!(!starts(
+            "2026-10-21T23:45:00+02:00",
+            "2026-10-22T01:00:00+02:00"
+        ));
+    }
+
+    #[test]
+    fn matches_programmes_on_air_across_midnight() {
+        let during = |start, end| Overlap::During.matches((at(start), at(end)), night());
+        assert!(during(
+            "2026-10-20T23:00:00+02:00",
+            "2026-10-21T02:00:00+02:00"
+        ));
+        assert!(during(
+            "2026-10-21T04:30:00+02:00",
+            "2026-10-21T06:00:00+02:00"
+        ));
+        assert!(during(
+            "2026-10-20T20:00:00+02:00",
+            "2026-10-21T08:00:00+02:00"
+        ));
+        assert!(!during(
+            "2026-10-20T22:00:00+02:00",
+            "2026-10-20T23:30:00+02:00"
+        ));
+        assert!(!during(
+            "2026-10-21T05:00:00+02:00",
+            "2026-10-21T06:00:00+02:00"
+        ));
+    }
+
+    #[test]
+    fn matches_ends_across_midnight() {
+        let ends = |start, end| Overlap::Ends.matches((at(start), at(end)), night());
+        assert!(ends(
+            "2026-10-20T23:00:00+02:00",
+            "2026-10-21T02:00:00+02:00"
+        ));
+        assert!(ends(
+            "2026-10-21T04:00:00+02:00",
+            "2026-10-21T05:00:00+02:00"
+        ));
+        assert!(!ends(
+            "2026-10-20T22:00:00+02:00",
+            "2026-10-20T23:30:00+02:00"
+        ));
+        assert!(!ends(
+            "2026-10-21T04:30:00+02:00",
+            "2026-10-21T06:00:00+02:00"
+        ));
+    }
+
+    #[test]
+    fn finds_slots_by_name_or_alias() {
+        assert_eq!(slot("Nuit").unwrap().name, "nuit");
+        assert_eq!(slot("prime").unwrap().name, "premiere-partie");
+        assert_eq!(slot("matinée").unwrap().name, "matinee");
+        assert!(matches!(slot("sieste"), Err(TvprogError::UnknownSlot(_))));
+    }
+}
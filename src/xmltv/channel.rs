@@ -0,0 +1,48 @@
+//! The `<channel>` element.
+
+use super::element::Element;
+use super::{Icon, LangText};
+
+/// A `<channel>` element.
+#[derive(Clone, Debug, PartialEq)]
+pub struct XMLChannel {
+    pub id: String,
+    pub display_names: Vec<LangText>,
+    pub icons: Vec<Icon>,
+    pub urls: Vec<String>,
+    /// The element as read, to write it back unchanged.
+    pub element: Element,
+}
+
+impl XMLChannel {
+    pub fn from_element(element: Element) -> Self {
+        XMLChannel {
+            id: element.attribute("id").unwrap_or_default().to_owned(),
+            display_names: LangText::all(&element, "display-name"),
+            icons: element
+                .children_named("icon")
+                .map(Icon::from_element)
+                .collect(),
+            urls: element
+                .children_named("url")
+                .map(|url| url.text().trim().to_owned())
+                .collect(),
+            element,
+        }
+    }
+
+    /// The first display name of the document.
+    pub fn display_name(&self) -> &str {
+        self.display_names
+            .first()
+            .map(|name| name.value.as_str())
+            .unwrap_or_default()
+    }
+
+    /// Tells whether any of the display names is `name`.
+    pub fn is_named(&self, name: &str) -> bool {
+        self.display_names
+            .iter()
+            .any(|display_name| display_name.value == name)
+    }
+}
@@ -0,0 +1,88 @@
+//! Raw XMLTV document as published by the guide providers.
+
+mod channel;
+mod element;
+mod programme;
+mod reader;
+mod writer;
+
+use chrono::{DateTime, FixedOffset, NaiveDateTime};
+
+pub use channel::XMLChannel;
+pub use element::{Element, Node};
+pub use programme::{
+    Actor, Audio, Credits, EpisodeNum, Icon, Length, PreviouslyShown, Rating, Subtitles, Video,
+    XMLProgram,
+};
+pub use reader::XmltvReader;
+pub use writer::write_xmltv;
+
+use crate::error::{Result, TvprogError};
+
+/// Layout of the `start` and `stop` attributes.
+pub const TIMESTAMP_FORMAT: &str = "%Y%m%d%H%M%S %z";
+
+/// A text given in a language, such as `<title lang="fr">`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct LangText {
+    pub value: String,
+    pub lang: Option<String>,
+}
+
+impl LangText {
+    pub fn from_element(element: &Element) -> Self {
+        LangText {
+            value: element.text().trim().to_owned(),
+            lang: element.attribute("lang").map(str::to_owned),
+        }
+    }
+
+    /// Reads every child called `name` of `element`.
+    pub fn all(element: &Element, name: &str) -> Vec<Self> {
+        element
+            .children_named(name)
+            .map(LangText::from_element)
+            .collect()
+    }
+}
+
+/// A top-level element of the guide, as yielded by [`XmltvReader`].
+#[derive(Debug, PartialEq)]
+pub enum Item {
+    /// The `<tv>` root element, with its attributes only.
+    Tv(Element),
+    Channel(XMLChannel),
+    Programme(Box<XMLProgram>),
+}
+
+/// Parses an XMLTV timestamp such as `20220818204500 +0200`. As the DTD
+/// states, a timestamp without an offset is in UTC.
+pub fn parse_timestamp(value: &str) -> Result<DateTime<FixedOffset>> {
+    let value = value.trim();
+    let parsed = match value.contains(' ') {
+        true => DateTime::parse_from_str(value, TIMESTAMP_FORMAT),
+        false => NaiveDateTime::parse_from_str(value, "%Y%m%d%H%M%S")
+            .map(|naive| naive.and_utc().fixed_offset()),
+    };
+    parsed.map_err(|source| TvprogError::Timestamp {
+        value: value.to_owned(),
+        source,
+    })
+}
+
+/// Escapes text for element contents and quoted attribute values, of XML
+/// and HTML documents alike.
+pub fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
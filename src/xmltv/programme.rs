@@ -0,0 +1,363 @@
+//! The `<programme>` element and its children, as described by the XMLTV DTD.
+
+use std::time::Duration;
+
+use serde::Serialize;
+
+use super::element::Element;
+use super::LangText;
+
+/// A `<programme>` element, with its timestamps left as XMLTV strings.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct XMLProgram {
+    pub start: String,
+    pub stop: String,
+    pub channel: String,
+    pub titles: Vec<LangText>,
+    pub sub_titles: Vec<LangText>,
+    pub descriptions: Vec<LangText>,
+    pub credits: Credits,
+    pub date: Option<String>,
+    pub categories: Vec<String>,
+    pub length: Option<Length>,
+    pub icons: Vec<Icon>,
+    pub episode_nums: Vec<EpisodeNum>,
+    pub video: Option<Video>,
+    pub audio: Option<Audio>,
+    pub previously_shown: Option<PreviouslyShown>,
+    pub premiere: bool,
+    pub new: bool,
+    pub subtitles: Vec<Subtitles>,
+    pub ratings: Vec<Rating>,
+    pub star_ratings: Vec<Rating>,
+    /// The element as read, to write it back unchanged.
+    pub element: Element,
+}
+
+/// The people taking part in a programme, from `<credits>`.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct Credits {
+    pub directors: Vec<String>,
+    pub actors: Vec<Actor>,
+    pub writers: Vec<String>,
+    pub adapters: Vec<String>,
+    pub producers: Vec<String>,
+    pub composers: Vec<String>,
+    pub editors: Vec<String>,
+    pub presenters: Vec<String>,
+    pub commentators: Vec<String>,
+    pub guests: Vec<String>,
+}
+
+/// An `<actor>`, with the part they play when known.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct Actor {
+    pub name: String,
+    pub role: Option<String>,
+}
+
+/// A `<length>`, e.g. `<length units="minutes">90</length>`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Length(pub Duration);
+
+/// An `<icon>` pointing at an image.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct Icon {
+    pub src: String,
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+}
+
+/// An `<episode-num>` in one of the numbering systems.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct EpisodeNum {
+    pub system: String,
+    pub value: String,
+}
+
+/// The `<video>` characteristics.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct Video {
+    pub present: Option<bool>,
+    pub colour: Option<bool>,
+    pub aspect: Option<String>,
+    pub quality: Option<String>,
+}
+
+/// The `<audio>` characteristics.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct Audio {
+    pub present: Option<bool>,
+    pub stereo: Option<String>,
+}
+
+/// A `<previously-shown>` marker, with the first broadcast when known.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct PreviouslyShown {
+    pub start: Option<String>,
+    pub channel: Option<String>,
+}
+
+/// A `<subtitles>` track.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct Subtitles {
+    pub kind: Option<String>,
+    pub language: Option<String>,
+}
+
+/// A `<rating>` or `<star-rating>`, e.g. `-12` in the `CSA` system or `3/5`.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct Rating {
+    pub system: Option<String>,
+    pub value: String,
+    pub icons: Vec<Icon>,
+}
+
+impl XMLProgram {
+    pub fn from_element(element: Element) -> Self {
+        XMLProgram {
+            start: element.attribute("start").unwrap_or_default().to_owned(),
+            stop: element.attribute("stop").unwrap_or_default().to_owned(),
+            channel: element.attribute("channel").unwrap_or_default().to_owned(),
+            titles: LangText::all(&element, "title"),
+            sub_titles: LangText::all(&element, "sub-title"),
+            descriptions: LangText::all(&element, "desc"),
+            credits: element
+                .child("credits")
+                .map(Credits::from_element)
+                .unwrap_or_default(),
+            date: child_text(&element, "date"),
+            categories: element
+                .children_named("category")
+                .map(Element::text)
+                .collect(),
+            length: element.child("length").and_then(Length::from_element),
+            icons: element
+                .children_named("icon")
+                .map(Icon::from_element)
+                .collect(),
+            episode_nums: element
+                .children_named("episode-num")
+                .map(EpisodeNum::from_element)
+                .collect(),
+            video: element.child("video").map(Video::from_element),
+            audio: element.child("audio").map(Audio::from_element),
+            previously_shown: element
+                .child("previously-shown")
+                .map(PreviouslyShown::from_element),
+            premiere: element.child("premiere").is_some(),
+            new: element.child("new").is_some(),
+            subtitles: element
+                .children_named("subtitles")
+                .map(Subtitles::from_element)
+                .collect(),
+            ratings: element
+                .children_named("rating")
+                .map(Rating::from_element)
+                .collect(),
+            star_ratings: element
+                .children_named("star-rating")
+                .map(Rating::from_element)
+                .collect(),
+            element,
+        }
+    }
+}
+
+impl Credits {
+    fn from_element(element: &Element) -> Self {
+        let names = |name| element.children_named(name).map(Element::text).collect();
+        Credits {
+            directors: names("director"),
+            actors: element
+                .children_named("actor")
+                .map(|actor| Actor {
+                    name: actor.text(),
+                    role: actor.attribute("role").map(str::to_owned),
+                })
+                .collect(),
+            writers: names("writer"),
+            adapters: names("adapter"),
+            producers: names("producer"),
+            composers: names("composer"),
+            editors: names("editor"),
+            presenters: names("presenter"),
+            commentators: names("commentator"),
+            guests: names("guest"),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        *self == Credits::default()
+    }
+}
+
+impl Length {
+    fn from_element(element: &Element) -> Option<Self> {
+        let value: u64 = element.text().trim().parse().ok()?;
+        let seconds = match element.attribute("units")? {
+            "seconds" => value,
+            "minutes" => value.checked_mul(60)?,
+            "hours" => value.checked_mul(3600)?,
+            _ => return None,
+        };
+        Some(Length(Duration::from_secs(seconds)))
+    }
+}
+
+impl Icon {
+    pub(super) fn from_element(element: &Element) -> Self {
+        Icon {
+            src: element.attribute("src").unwrap_or_default().to_owned(),
+            width: element
+                .attribute("width")
+                .and_then(|width| width.parse().ok()),
+            height: element
+                .attribute("height")
+                .and_then(|height| height.parse().ok()),
+        }
+    }
+}
+
+impl EpisodeNum {
+    fn from_element(element: &Element) -> Self {
+        EpisodeNum {
+            system: element.attribute("system").unwrap_or("onscreen").to_owned(),
+            value: element.text().trim().to_owned(),
+        }
+    }
+
+    /// Season and episode, counted from 1, of an `xmltv_ns` number such as
+    /// `2.11/24.0/1` (season 3, episode 12).
+    pub fn season_episode(&self) -> Option<(Option<u32>, Option<u32>)> {
+        if self.system != "xmltv_ns" {
+            return None;
+        }
+        let mut parts = self.value.split('.');
+        let mut number = || {
+            parts
+                .next()
+                .and_then(|part| part.split('/').next())
+                .and_then(|part| part.trim().parse::<u32>().ok())
+                .and_then(|n| n.checked_add(1))
+        };
+        Some((number(), number()))
+    }
+}
+
+impl Video {
+    fn from_element(element: &Element) -> Self {
+        Video {
+            present: child_text(element, "present").map(|value| value == "yes"),
+            colour: child_text(element, "colour").map(|value| value == "yes"),
+            aspect: child_text(element, "aspect"),
+            quality: child_text(element, "quality"),
+        }
+    }
+}
+
+impl Audio {
+    fn from_element(element: &Element) -> Self {
+        Audio {
+            present: child_text(element, "present").map(|value| value == "yes"),
+            stereo: child_text(element, "stereo"),
+        }
+    }
+}
+
+impl PreviouslyShown {
+    fn from_element(element: &Element) -> Self {
+        PreviouslyShown {
+            start: element.attribute("start").map(str::to_owned),
+            channel: element.attribute("channel").map(str::to_owned),
+        }
+    }
+}
+
+impl Subtitles {
+    fn from_element(element: &Element) -> Self {
+        Subtitles {
+            kind: element.attribute("type").map(str::to_owned),
+            language: child_text(element, "language"),
+        }
+    }
+}
+
+impl Rating {
+    fn from_element(element: &Element) -> Self {
+        Rating {
+            system: element.attribute("system").map(str::to_owned),
+            value: child_text(element, "value").unwrap_or_default(),
+            icons: element
+                .children_named("icon")
+                .map(Icon::from_element)
+                .collect(),
+        }
+    }
+}
+
+fn child_text(element: &Element, name: &str) -> Option<String> {
+    element
+        .child(name)
+        .map(|child| child.text().trim().to_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::xmltv::Node;
+
+    fn episode_num(system: &str, value: &str) -> EpisodeNum {
+        EpisodeNum {
+            system: system.to_owned(),
+            value: value.to_owned(),
+        }
+    }
+
+    #[test]
+    fn counts_seasons_and_episodes_from_one() {
+        let season_episode = |value| episode_num("xmltv_ns", value).season_episode();
+        assert_eq!(season_episode("2.11/24.0/1"), Some((Some(3), Some(12))));
+        assert_eq!(season_episode("1.4."), Some((Some(2), Some(5))));
+        assert_eq!(season_episode(" 0 . 0 . "), Some((Some(1), Some(1))));
+        assert_eq!(season_episode(".4."), Some((None, Some(5))));
+        assert_eq!(season_episode("2.."), Some((Some(3), None)));
+        assert_eq!(season_episode(".."), Some((None, None)));
+        assert_eq!(season_episode("x.y."), Some((None, None)));
+    }
+
+    #[test]
+    fn ignores_numbers_and_lengths_that_overflow() {
+        let max = u32::MAX.to_string();
+        let season_episode = |value: &str| episode_num("xmltv_ns", value).season_episode();
+        assert_eq!(
+            season_episode(&format!("{}.3.", max)),
+            Some((None, Some(4)))
+        );
+        assert_eq!(season_episode(&format!(".{}.", max)), Some((None, None)));
+
+        let length = |value: u64, units: &str| {
+            Length::from_element(&Element {
+                name: "length".to_owned(),
+                attributes: vec![("units".to_owned(), units.to_owned())],
+                children: vec![Node::Text(value.to_string())],
+            })
+        };
+        assert_eq!(
+            length(90, "minutes"),
+            Some(Length(Duration::from_secs(5400)))
+        );
+        assert_eq!(length(u64::MAX / 60 + 1, "minutes"), None);
+        assert_eq!(length(u64::MAX / 3600 + 1, "hours"), None);
+        assert_eq!(
+            length(u64::MAX, "seconds"),
+            Some(Length(Duration::from_secs(u64::MAX)))
+        );
+    }
+
+    #[test]
+    fn reads_only_xmltv_ns_numbers() {
+        assert_eq!(episode_num("onscreen", "S03E12").season_episode(), None);
+        assert_eq!(episode_num("dd_progid", "EP00123").season_episode(), None);
+    }
+}
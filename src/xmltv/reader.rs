@@ -0,0 +1,244 @@
+//! Streaming reader yielding channels and programmes as they are parsed.
+
+use std::io::BufRead;
+
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+
+use super::element::{Element, Node};
+use super::{Item, XMLChannel, XMLProgram};
+use crate::error::Result;
+
+/// Iterates over the `<channel>` and `<programme>` elements of an XMLTV
+/// document, holding a single one of them in memory at a time.
+pub struct XmltvReader<R: BufRead> {
+    reader: Reader<R>,
+    buf: Vec<u8>,
+    done: bool,
+}
+
+impl<R: BufRead> XmltvReader<R> {
+    pub fn new(input: R) -> Self {
+        let mut reader = Reader::from_reader(input);
+        reader.trim_text(true);
+        XmltvReader {
+            reader,
+            buf: Vec::new(),
+            done: false,
+        }
+    }
+
+    fn next_item(&mut self) -> Result<Option<Item>> {
+        loop {
+            self.buf.clear();
+            let (start, empty) = match self.reader.read_event(&mut self.buf)? {
+                Event::Start(start) => (start.into_owned(), false),
+                Event::Empty(start) => (start.into_owned(), true),
+                Event::Eof => return Ok(None),
+                _ => continue,
+            };
+
+            let item = match start.name() {
+                b"tv" => Item::Tv(self.start_element(&start)?),
+                b"channel" => {
+                    Item::Channel(XMLChannel::from_element(self.read_element(start, empty)?))
+                }
+                b"programme" => {
+                    let element = self.read_element(start, empty)?;
+                    Item::Programme(Box::new(XMLProgram::from_element(element)))
+                }
+                _ => continue,
+            };
+            return Ok(Some(item));
+        }
+    }
+
+    /// Reads the name and attributes of the element starting with `start`.
+    fn start_element(&self, start: &BytesStart) -> Result<Element> {
+        let mut element = Element {
+            name: self.reader.decode(start.name())?.to_owned(),
+            ..Element::default()
+        };
+        for attribute in start.attributes() {
+            let attribute = attribute.map_err(quick_xml::Error::from)?;
+            let key = self.reader.decode(attribute.key)?.to_owned();
+            let value = attribute.unescape_and_decode_value(&self.reader)?;
+            element.attributes.push((key, value));
+        }
+        Ok(element)
+    }
+
+    fn read_element(&mut self, start: BytesStart, empty: bool) -> Result<Element> {
+        let mut element = self.start_element(&start)?;
+        if empty {
+            return Ok(element);
+        }
+
+        let mut buf = Vec::new();
+        loop {
+            buf.clear();
+            match self.reader.read_event(&mut buf)? {
+                Event::Start(child) => {
+                    let child = self.read_element(child.into_owned(), false)?;
+                    element.children.push(Node::Element(child));
+                }
+                Event::Empty(child) => {
+                    let child = self.read_element(child.into_owned(), true)?;
+                    element.children.push(Node::Element(child));
+                }
+                Event::Text(text) => {
+                    let text = text.unescape_and_decode(&self.reader)?;
+                    element.children.push(Node::Text(text));
+                }
+                Event::CData(text) => {
+                    let text = self.reader.decode(&text)?.to_owned();
+                    element.children.push(Node::Text(text));
+                }
+                Event::End(_) => return Ok(element),
+                Event::Eof => {
+                    return Err(quick_xml::Error::UnexpectedEof(element.name).into());
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+impl<R: BufRead> Iterator for XmltvReader<R> {
+    type Item = Result<Item>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let item = self.next_item();
+        if !matches!(item, Ok(Some(_))) {
+            self.done = true;
+        }
+        item.transpose()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::TvprogError;
+    use crate::fixtures::GUIDE;
+    use crate::lang::Languages;
+    use crate::xmltv::LangText;
+
+    fn items(xml: &str) -> Vec<Item> {
+        XmltvReader::new(xml.as_bytes())
+            .collect::<Result<_>>()
+            .unwrap()
+    }
+
+    fn lang_text(value: &str, lang: Option<&str>) -> LangText {
+        LangText {
+            value: value.to_owned(),
+            lang: lang.map(str::to_owned),
+        }
+    }
+
+    #[test]
+    fn yields_the_root_then_channels_and_programmes_in_order() {
+        let items = items(GUIDE);
+        let kinds: Vec<&str> = items
+            .iter()
+            .map(|item| match item {
+                Item::Tv(_) => "tv",
+                Item::Channel(_) => "channel",
+                Item::Programme(_) => "programme",
+            })
+            .collect();
+        assert_eq!(
+            kinds,
+            ["tv", "channel", "channel", "programme", "programme"]
+        );
+
+        let Item::Tv(tv) = &items[0] else {
+            panic!("expected the root element");
+        };
+        assert_eq!(tv.attribute("source-info-name"), Some("Test"));
+        assert!(tv.children.is_empty());
+    }
+
+    #[test]
+    fn keeps_every_display_name_of_a_channel() {
+        let Item::Channel(channel) = &items(GUIDE)[1] else {
+            panic!("expected a channel");
+        };
+        assert_eq!(channel.id, "tf1.fr");
+        assert_eq!(
+            channel.display_names,
+            [
+                lang_text("TF1", Some("fr")),
+                lang_text("TF 1", None),
+                lang_text("1", None),
+            ]
+        );
+        assert_eq!(channel.display_name(), "TF1");
+        assert!(channel.is_named("TF 1"));
+        assert!(channel.is_named("1"));
+        assert!(!channel.is_named("tf1"));
+        assert_eq!(channel.icons[0].src, "https://example.org/tf1.png");
+    }
+
+    #[test]
+    fn keeps_the_titles_in_every_language() {
+        let Item::Programme(program) = &items(GUIDE)[3] else {
+            panic!("expected a programme");
+        };
+        assert_eq!(
+            program.titles,
+            [
+                lang_text("Koh-Lanta, la légende", Some("fr")),
+                lang_text("Survivor", Some("en-GB")),
+            ]
+        );
+
+        let pick = |languages: &str| {
+            languages
+                .parse::<Languages>()
+                .unwrap()
+                .text(&program.titles)
+                .unwrap()
+        };
+        assert_eq!(pick("fr"), "Koh-Lanta, la légende");
+        assert_eq!(pick("en"), "Survivor");
+        assert_eq!(pick("de,en"), "Survivor");
+        // Without a match nor an untagged title, the first one is used.
+        assert_eq!(pick("de"), "Koh-Lanta, la légende");
+    }
+
+    #[test]
+    fn reads_attributes_texts_and_flags() {
+        let Item::Programme(program) = &items(GUIDE)[3] else {
+            panic!("expected a programme");
+        };
+        assert_eq!(program.start, "20261020210000 +0200");
+        assert_eq!(program.stop, "20261020225000 +0200");
+        assert_eq!(program.channel, "tf1.fr");
+        assert_eq!(
+            program.descriptions[0].value,
+            "Les \"aventuriers\" reviennent & repartent."
+        );
+        assert_eq!(program.categories, ["Jeu", "Téléréalité"]);
+        assert!(program.new);
+        assert!(!program.premiere);
+
+        let Item::Programme(program) = &items(GUIDE)[4] else {
+            panic!("expected a programme");
+        };
+        assert_eq!(program.sub_titles[0].value, "Un \"témoin\", enfin");
+        assert_eq!(program.element.name, "programme");
+    }
+
+    #[test]
+    fn fails_on_a_truncated_document() {
+        let truncated = &GUIDE[..GUIDE.find("<category>Série").unwrap()];
+        let results: Vec<Result<Item>> = XmltvReader::new(truncated.as_bytes()).collect();
+        assert!(matches!(results.last(), Some(Err(TvprogError::Decode(_)))));
+        assert_eq!(results.iter().filter(|result| result.is_ok()).count(), 4);
+    }
+}
@@ -0,0 +1,134 @@
+//! Writing of `<channel>` and `<programme>` elements back into an XMLTV
+//! document.
+
+use std::io::{self, Write};
+
+use super::element::{Element, Node};
+use super::escape;
+
+/// Writes a document whose root has the attributes of `tv`, holding the
+/// `channels` then the `programmes` with their attributes and children as
+/// read.
+pub fn write_xmltv<'a>(
+    tv: &Element,
+    channels: impl IntoIterator<Item = &'a Element>,
+    programmes: impl IntoIterator<Item = &'a Element>,
+    out: &mut impl Write,
+) -> io::Result<()> {
+    writeln!(out, "<?xml version=\"1.0\" encoding=\"UTF-8\"?>")?;
+    writeln!(out, "<!DOCTYPE tv SYSTEM \"xmltv.dtd\">")?;
+    write!(out, "<tv")?;
+    write_attributes(tv, out)?;
+    writeln!(out, ">")?;
+    for element in channels.into_iter().chain(programmes) {
+        write_element(element, 1, out)?;
+    }
+    writeln!(out, "</tv>")
+}
+
+/// Writes `element` on its own lines, indented by `depth` levels. Elements
+/// holding text are kept on one line so that the text is left as it was.
+fn write_element(element: &Element, depth: usize, out: &mut impl Write) -> io::Result<()> {
+    let indent = "  ".repeat(depth);
+    write!(out, "{}", indent)?;
+    write_start(element, out)?;
+    if element.children.is_empty() {
+        return writeln!(out);
+    }
+
+    let has_text = element
+        .children
+        .iter()
+        .any(|node| matches!(node, Node::Text(_)));
+    if has_text {
+        write_children(element, out)?;
+        return writeln!(out, "</{}>", element.name);
+    }
+
+    writeln!(out)?;
+    for node in &element.children {
+        if let Node::Element(child) = node {
+            write_element(child, depth + 1, out)?;
+        }
+    }
+    writeln!(out, "{}</{}>", indent, element.name)
+}
+
+/// Writes the start tag of `element`, self-closed when it has no children.
+fn write_start(element: &Element, out: &mut impl Write) -> io::Result<()> {
+    write!(out, "<{}", element.name)?;
+    write_attributes(element, out)?;
+    match element.children.is_empty() {
+        true => write!(out, "/>"),
+        false => write!(out, ">"),
+    }
+}
+
+fn write_attributes(element: &Element, out: &mut impl Write) -> io::Result<()> {
+    for (key, value) in &element.attributes {
+        write!(out, " {}=\"{}\"", key, escape(value))?;
+    }
+    Ok(())
+}
+
+/// Writes the children of `element` inline, with their end tags.
+fn write_children(element: &Element, out: &mut impl Write) -> io::Result<()> {
+    for node in &element.children {
+        match node {
+            Node::Text(text) => write!(out, "{}", escape(text))?,
+            Node::Element(child) => {
+                write_start(child, out)?;
+                if !child.children.is_empty() {
+                    write_children(child, out)?;
+                    write!(out, "</{}>", child.name)?;
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::Result;
+    use crate::fixtures::GUIDE;
+    use crate::xmltv::{Item, XmltvReader};
+
+    fn elements(xml: &[u8]) -> Vec<Element> {
+        XmltvReader::new(xml)
+            .map(|item| match item? {
+                Item::Tv(tv) => Ok(tv),
+                Item::Channel(channel) => Ok(channel.element),
+                Item::Programme(program) => Ok(program.element),
+            })
+            .collect::<Result<_>>()
+            .unwrap()
+    }
+
+    #[test]
+    fn writes_back_what_was_read() {
+        let read = elements(GUIDE.as_bytes());
+        let (channels, programmes) = read[1..].split_at(2);
+        let mut out = Vec::new();
+        write_xmltv(&read[0], channels, programmes, &mut out).unwrap();
+
+        assert!(out.starts_with(b"<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n"));
+        assert_eq!(elements(&out), read);
+    }
+
+    #[test]
+    fn escapes_attributes_and_texts() {
+        let tv = Element {
+            name: "tv".to_owned(),
+            attributes: vec![("source-info-name".to_owned(), "A \"&\" <B>".to_owned())],
+            children: Vec::new(),
+        };
+        let mut out = Vec::new();
+        write_xmltv(&tv, [], [], &mut out).unwrap();
+        let written = String::from_utf8(out).unwrap();
+
+        assert!(written.contains("<tv source-info-name=\"A &quot;&amp;&quot; &lt;B&gt;\">"));
+        assert_eq!(elements(written.as_bytes()), [tv]);
+    }
+}